@@ -0,0 +1,228 @@
+use crate::Lux;
+
+/// Mapping from ambient light to display brightness
+///
+/// Brightness values range from 0 (off) to 1 (full brightness).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrightnessCurve<'a> {
+    /// Brightness proportional to the logarithm of the lux value, which
+    /// matches the perception of the human eye.
+    ///
+    /// Below `min_lux` and above `max_lux` the brightness stays at
+    /// `min_brightness` and `max_brightness` respectively.
+    Logarithmic {
+        /// Lux value for the minimum brightness
+        min_lux: Lux,
+        /// Lux value for the maximum brightness
+        max_lux: Lux,
+        /// Minimum brightness
+        min_brightness: f32,
+        /// Maximum brightness
+        max_brightness: f32,
+    },
+    /// Linear interpolation between `(lux, brightness)` points sorted by
+    /// ascending lux value.
+    ///
+    /// Below the first and above the last point the brightness of these
+    /// points is used. Without points the brightness is 1.
+    Piecewise(&'a [(Lux, f32)]),
+}
+
+impl BrightnessCurve<'_> {
+    /// Calculate the brightness for a lux value.
+    pub fn brightness(&self, lux: Lux) -> f32 {
+        let brightness = match *self {
+            BrightnessCurve::Logarithmic {
+                min_lux,
+                max_lux,
+                min_brightness,
+                max_brightness,
+            } => {
+                if lux <= min_lux {
+                    min_brightness
+                } else if lux >= max_lux {
+                    max_brightness
+                } else {
+                    let position = libm::logf(lux.0 / min_lux.0.max(f32::MIN_POSITIVE))
+                        / libm::logf(max_lux.0 / min_lux.0.max(f32::MIN_POSITIVE));
+                    min_brightness + (max_brightness - min_brightness) * position
+                }
+            }
+            BrightnessCurve::Piecewise(points) => interpolate(points, lux),
+        };
+        brightness.clamp(0.0, 1.0)
+    }
+}
+
+fn interpolate(points: &[(Lux, f32)], lux: Lux) -> f32 {
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return 1.0,
+    };
+    if lux <= first.0 {
+        return first.1;
+    }
+    for pair in points.windows(2) {
+        let ((lux0, brightness0), (lux1, brightness1)) = (pair[0], pair[1]);
+        if lux <= lux1 {
+            if lux1.0 <= lux0.0 {
+                return brightness1;
+            }
+            let position = (lux.0 - lux0.0) / (lux1.0 - lux0.0);
+            return brightness0 + (brightness1 - brightness0) * position;
+        }
+    }
+    last.1
+}
+
+/// Display brightness controller for lux readings
+///
+/// Maps the readings to a brightness with a [`BrightnessCurve`]. To avoid
+/// visible steps, the target brightness is only recalculated once the lux
+/// value differs from the one used last time by more than the hysteresis,
+/// and the brightness changes at most by the maximum step per reading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BacklightController<'a> {
+    curve: BrightnessCurve<'a>,
+    hysteresis_percent: f32,
+    max_step: f32,
+    reference: Option<Lux>,
+    target: f32,
+    brightness: f32,
+}
+
+impl<'a> BacklightController<'a> {
+    /// Create a new controller using the given curve.
+    ///
+    /// There is no hysteresis and no ramp limit.
+    pub fn new(curve: BrightnessCurve<'a>) -> Self {
+        BacklightController {
+            curve,
+            hysteresis_percent: 0.0,
+            max_step: 1.0,
+            reference: None,
+            target: 0.0,
+            brightness: 0.0,
+        }
+    }
+
+    /// Set the relative lux change in percent needed to recalculate the
+    /// target brightness.
+    pub fn with_hysteresis(self, percent: f32) -> Self {
+        BacklightController {
+            hysteresis_percent: percent,
+            ..self
+        }
+    }
+
+    /// Set the maximum brightness change per reading.
+    ///
+    /// The ramp rate thus depends on how often readings are processed.
+    pub fn with_max_step(self, max_step: f32) -> Self {
+        BacklightController { max_step, ..self }
+    }
+
+    /// Process a new reading and return the brightness to apply.
+    ///
+    /// The first reading sets the brightness directly.
+    pub fn update(&mut self, lux: Lux) -> f32 {
+        match self.reference {
+            None => {
+                self.reference = Some(lux);
+                self.target = self.curve.brightness(lux);
+                self.brightness = self.target;
+            }
+            Some(reference) => {
+                let hysteresis = reference.0 * self.hysteresis_percent / 100.0;
+                if libm::fabsf(lux.0 - reference.0) > hysteresis {
+                    self.reference = Some(lux);
+                    self.target = self.curve.brightness(lux);
+                }
+                let step = (self.target - self.brightness).clamp(-self.max_step, self.max_step);
+                self.brightness += step;
+            }
+        }
+        self.brightness
+    }
+
+    /// Current brightness.
+    pub fn brightness(&self) -> f32 {
+        self.brightness
+    }
+
+    /// Target brightness the current brightness is ramping towards.
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Forget the previous readings so that the next one sets the
+    /// brightness directly.
+    pub fn reset(&mut self) {
+        self.reference = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: BrightnessCurve = BrightnessCurve::Logarithmic {
+        min_lux: Lux(1.0),
+        max_lux: Lux(10000.0),
+        min_brightness: 0.1,
+        max_brightness: 0.9,
+    };
+
+    const POINTS: [(Lux, f32); 3] = [(Lux(10.0), 0.2), (Lux(100.0), 0.5), (Lux(1000.0), 1.0)];
+
+    fn assert_near(expected: f32, actual: f32) {
+        assert!(
+            (expected - actual).abs() < 1e-5,
+            "{} != {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn logarithmic() {
+        assert_near(0.1, LOG.brightness(Lux(0.0)));
+        assert_near(0.1, LOG.brightness(Lux(1.0)));
+        assert_near(0.3, LOG.brightness(Lux(10.0)));
+        assert_near(0.5, LOG.brightness(Lux(100.0)));
+        assert_near(0.9, LOG.brightness(Lux(10000.0)));
+        assert_near(0.9, LOG.brightness(Lux(100000.0)));
+    }
+
+    #[test]
+    fn piecewise() {
+        let curve = BrightnessCurve::Piecewise(&POINTS);
+        assert_near(0.2, curve.brightness(Lux(1.0)));
+        assert_near(0.35, curve.brightness(Lux(55.0)));
+        assert_near(0.5, curve.brightness(Lux(100.0)));
+        assert_near(1.0, curve.brightness(Lux(5000.0)));
+        assert_near(1.0, BrightnessCurve::Piecewise(&[]).brightness(Lux(5.0)));
+    }
+
+    #[test]
+    fn controller_applies_hysteresis() {
+        let mut controller =
+            BacklightController::new(BrightnessCurve::Piecewise(&POINTS)).with_hysteresis(10.0);
+        assert_near(0.5, controller.update(Lux(100.0)));
+        assert_near(0.5, controller.update(Lux(109.0)));
+        assert_near(0.5, controller.update(Lux(91.0)));
+        assert_near(0.5 + 0.5 * 50.0 / 900.0, controller.update(Lux(150.0)));
+    }
+
+    #[test]
+    fn controller_limits_ramp() {
+        let mut controller =
+            BacklightController::new(BrightnessCurve::Piecewise(&POINTS)).with_max_step(0.2);
+        assert_near(0.2, controller.update(Lux(10.0)));
+        assert_near(0.4, controller.update(Lux(1000.0)));
+        assert_near(0.6, controller.update(Lux(1000.0)));
+        assert_near(1.0, controller.target());
+        controller.reset();
+        assert_near(1.0, controller.update(Lux(1000.0)));
+    }
+}
@@ -0,0 +1,194 @@
+use crate::Measurement;
+
+/// Light change detected between readings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightChange {
+    /// No significant change
+    Steady,
+    /// The light increased abruptly, for example a door was opened.
+    SuddenIncrease,
+    /// The light decreased abruptly, for example due to a shadow.
+    SuddenDecrease,
+    /// Both channels dropped to nearly zero after bright readings, for
+    /// example because the sensor was covered.
+    Obstructed,
+}
+
+/// Detector for sudden light changes and sensor obstruction
+///
+/// Works on raw ALS and white channel counts read with the same gain and
+/// integration time. See [`read_raw()`](struct.Veml6030.html#method.read_raw)
+/// and [`read_white()`](struct.Veml6030.html#method.read_white).
+///
+/// A change is sudden if the ALS value differs from the previous reading
+/// by more than the configured percentage and by at least the minimum
+/// count difference. The sensor is considered obstructed while both
+/// channels are at or below the dark count and the recent level, an
+/// exponential average of the ALS readings before, was at least the bright
+/// count.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChangeDetector {
+    change_percent: f32,
+    min_change_counts: u16,
+    dark_max_counts: u16,
+    bright_min_counts: u16,
+    previous: Option<u16>,
+    recent_level: f32,
+    obstructed: bool,
+}
+
+impl ChangeDetector {
+    /// Averaging factor of the recent level
+    const RECENT_LEVEL_ALPHA: f32 = 0.2;
+
+    /// Create a new detector flagging changes by more than the given
+    /// percentage.
+    ///
+    /// Changes must be at least 10 counts. The sensor is considered
+    /// obstructed at 5 counts or less after a recent level of at least
+    /// 100 counts.
+    pub fn new(change_percent: f32) -> Self {
+        ChangeDetector {
+            change_percent,
+            min_change_counts: 10,
+            dark_max_counts: 5,
+            bright_min_counts: 100,
+            previous: None,
+            recent_level: 0.0,
+            obstructed: false,
+        }
+    }
+
+    /// Set the minimum count difference of a sudden change.
+    ///
+    /// This keeps noise at low light levels from being flagged.
+    pub fn with_min_change_counts(self, counts: u16) -> Self {
+        ChangeDetector {
+            min_change_counts: counts,
+            ..self
+        }
+    }
+
+    /// Set the maximum counts of both channels when obstructed and the
+    /// minimum recent ALS level needed beforehand.
+    pub fn with_obstruction(self, dark_max_counts: u16, bright_min_counts: u16) -> Self {
+        ChangeDetector {
+            dark_max_counts,
+            bright_min_counts,
+            ..self
+        }
+    }
+
+    /// Process new raw ALS and white channel readings.
+    ///
+    /// The first reading is always steady.
+    pub fn update(&mut self, raw_als: u16, raw_white: u16) -> LightChange {
+        let is_dark = raw_als <= self.dark_max_counts && raw_white <= self.dark_max_counts;
+        let previous = self.previous.replace(raw_als);
+        if is_dark && (self.obstructed || self.recent_level >= f32::from(self.bright_min_counts)) {
+            self.obstructed = true;
+            return LightChange::Obstructed;
+        }
+        self.obstructed = false;
+        let previous = match previous {
+            Some(previous) => previous,
+            None => {
+                self.recent_level = f32::from(raw_als);
+                return LightChange::Steady;
+            }
+        };
+        self.recent_level += Self::RECENT_LEVEL_ALPHA * (f32::from(raw_als) - self.recent_level);
+        let difference = i32::from(raw_als) - i32::from(previous);
+        let threshold = f32::from(previous) * self.change_percent / 100.0;
+        if difference.unsigned_abs() < u32::from(self.min_change_counts)
+            || (difference.abs() as f32) <= threshold
+        {
+            LightChange::Steady
+        } else if difference > 0 {
+            LightChange::SuddenIncrease
+        } else {
+            LightChange::SuddenDecrease
+        }
+    }
+
+    /// Process a measurement.
+    ///
+    /// See [`read_all()`](struct.Veml6030.html#method.read_all).
+    pub fn update_measurement(&mut self, measurement: &Measurement) -> LightChange {
+        self.update(measurement.raw_als, measurement.raw_white)
+    }
+
+    /// Whether the sensor is currently considered obstructed.
+    pub fn is_obstructed(&self) -> bool {
+        self.obstructed
+    }
+
+    /// Forget all previous readings.
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.recent_level = 0.0;
+        self.obstructed = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InterruptStatus, Lux};
+
+    #[test]
+    fn detects_sudden_changes() {
+        let mut detector = ChangeDetector::new(30.0);
+        assert_eq!(LightChange::Steady, detector.update(1000, 1200));
+        assert_eq!(LightChange::Steady, detector.update(1200, 1400));
+        assert_eq!(LightChange::SuddenDecrease, detector.update(500, 600));
+        assert_eq!(LightChange::SuddenIncrease, detector.update(1000, 1200));
+    }
+
+    #[test]
+    fn ignores_small_count_changes() {
+        let mut detector = ChangeDetector::new(30.0).with_min_change_counts(20);
+        assert_eq!(LightChange::Steady, detector.update(20, 30));
+        assert_eq!(LightChange::Steady, detector.update(35, 50));
+        assert_eq!(LightChange::SuddenIncrease, detector.update(60, 80));
+    }
+
+    #[test]
+    fn detects_obstruction() {
+        let mut detector = ChangeDetector::new(30.0);
+        assert_eq!(LightChange::Steady, detector.update(1000, 1200));
+        assert_eq!(LightChange::Obstructed, detector.update(2, 3));
+        assert!(detector.is_obstructed());
+        assert_eq!(LightChange::Obstructed, detector.update(0, 0));
+        assert_eq!(LightChange::SuddenIncrease, detector.update(1000, 1200));
+        assert!(!detector.is_obstructed());
+    }
+
+    #[test]
+    fn darkness_after_dim_light_is_not_obstruction() {
+        let mut detector = ChangeDetector::new(30.0).with_obstruction(5, 500);
+        assert_eq!(LightChange::Steady, detector.update(200, 250));
+        assert_eq!(LightChange::SuddenDecrease, detector.update(2, 3));
+        assert!(!detector.is_obstructed());
+    }
+
+    #[test]
+    fn processes_measurements() {
+        let mut detector = ChangeDetector::new(30.0);
+        let measurement = Measurement {
+            raw_als: 1000,
+            raw_white: 1200,
+            lux: Lux(57.6),
+            interrupt_status: InterruptStatus {
+                was_too_low: false,
+                was_too_high: false,
+            },
+        };
+        assert_eq!(
+            LightChange::Steady,
+            detector.update_measurement(&measurement)
+        );
+        detector.reset();
+        assert_eq!(LightChange::Steady, detector.update(0, 0));
+    }
+}
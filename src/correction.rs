@@ -1,12 +1,13 @@
-use crate::{Gain, IntegrationTime};
+use crate::{Gain, IntegrationTime, Lux};
 
 /// Calculate raw value for threshold applying compensation if necessary.
 ///
 /// For values higher than 1000 lx and 1/4 or 1/8 gain, the inverse of the
 /// compensation formula is applied. This involves quite some math so it
 /// may be interesting to calculate the threshold values ahead of time.
-pub fn calculate_raw_threshold_value(it: IntegrationTime, gain: Gain, lux: f32) -> u16 {
+pub fn calculate_raw_threshold_value(it: IntegrationTime, gain: Gain, lux: Lux) -> u16 {
     let factor = get_lux_raw_conversion_factor(it, gain);
+    let lux = lux.0;
     if (gain == Gain::OneQuarter || gain == Gain::OneEighth) && lux > 1000.0 {
         let lux = inverse_high_lux_correction(f64::from(lux));
         (lux / f64::from(factor)) as u16
@@ -15,6 +16,25 @@ pub fn calculate_raw_threshold_value(it: IntegrationTime, gain: Gain, lux: f32)
     }
 }
 
+/// Lux value corresponding to one ALS count.
+///
+/// This is the resolution without compensation. For values higher than
+/// 1000 lx and 1/4 or 1/8 gain, the compensation formula makes the effective
+/// step between consecutive counts larger.
+pub fn resolution_lux_per_count(it: IntegrationTime, gain: Gain) -> f32 {
+    get_lux_raw_conversion_factor(it, gain)
+}
+
+/// Maximum measurable lux value.
+///
+/// This is the lux value of a full-scale ALS measurement without
+/// compensation, as listed in the datasheet. The compensation formula is
+/// not applied since it yields values far beyond the specified range of the
+/// device (about 120 klx) for full-scale measurements with 1/4 or 1/8 gain.
+pub fn max_lux(it: IntegrationTime, gain: Gain) -> Lux {
+    Lux(get_lux_raw_conversion_factor(it, gain) * f32::from(u16::MAX))
+}
+
 pub(crate) fn get_lux_raw_conversion_factor(it: IntegrationTime, gain: Gain) -> f32 {
     let gain_factor = match gain {
         Gain::Two => 1.0,
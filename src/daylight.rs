@@ -0,0 +1,254 @@
+use crate::{calculate_raw_threshold_value, Gain, IntegrationTime, Lux, ThresholdWindow};
+
+/// Ambient light condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DaylightState {
+    /// Night
+    Night,
+    /// Dawn or dusk
+    Twilight,
+    /// Daylight
+    Day,
+    /// Direct sunlight
+    BrightSun,
+}
+
+impl DaylightState {
+    const ALL: [DaylightState; 4] = [
+        DaylightState::Night,
+        DaylightState::Twilight,
+        DaylightState::Day,
+        DaylightState::BrightSun,
+    ];
+}
+
+/// Classifier turning lux readings into day, twilight and night states
+///
+/// A state is left only once the lux value is beyond the boundary by more
+/// than the hysteresis and the new state has persisted for the minimum
+/// dwell time.
+///
+/// Instead of polling, the classifier can be driven by the threshold
+/// interrupt: program the window returned by
+/// [`threshold_window()`](#method.threshold_window) with
+/// [`set_threshold_window()`](struct.Veml6030.html#method.set_threshold_window),
+/// update the classifier with the lux value read after each interrupt and
+/// program the new window. In this case, the minimum dwell time is only
+/// evaluated on interrupts, so the fault count is better suited to filter
+/// short changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DaylightClassifier {
+    boundaries: [Lux; 3],
+    hysteresis_percent: f32,
+    min_dwell_ms: u64,
+    state: Option<DaylightState>,
+    pending: Option<(DaylightState, u64)>,
+}
+
+impl Default for DaylightClassifier {
+    /// Boundaries at 5 lx, 400 lx and 30 klx with 10 % hysteresis and no
+    /// minimum dwell time.
+    fn default() -> Self {
+        DaylightClassifier::new(Lux(5.0), Lux(400.0), Lux(30000.0)).with_hysteresis(10.0)
+    }
+}
+
+impl DaylightClassifier {
+    /// Create a new classifier with the upper lux boundaries of the night,
+    /// twilight and day states.
+    ///
+    /// There is no hysteresis and no minimum dwell time.
+    pub fn new(night_max: Lux, twilight_max: Lux, day_max: Lux) -> Self {
+        DaylightClassifier {
+            boundaries: [night_max, twilight_max, day_max],
+            hysteresis_percent: 0.0,
+            min_dwell_ms: 0,
+            state: None,
+            pending: None,
+        }
+    }
+
+    /// Set the hysteresis as a percentage of the boundaries.
+    pub fn with_hysteresis(self, percent: f32) -> Self {
+        DaylightClassifier {
+            hysteresis_percent: percent,
+            ..self
+        }
+    }
+
+    /// Set the time in milliseconds a new state must persist before it is
+    /// reported.
+    pub fn with_min_dwell_ms(self, min_dwell_ms: u64) -> Self {
+        DaylightClassifier {
+            min_dwell_ms,
+            ..self
+        }
+    }
+
+    /// Process a new reading taken at the given time in milliseconds and
+    /// return the resulting state.
+    ///
+    /// The first reading sets the state directly.
+    pub fn update(&mut self, timestamp_ms: u64, lux: Lux) -> DaylightState {
+        let state = match self.state {
+            None => {
+                self.pending = None;
+                self.classify(lux)
+            }
+            Some(state) => {
+                let (low, high) = self.window(state);
+                let candidate = if lux < low || lux > high {
+                    self.classify(lux)
+                } else {
+                    state
+                };
+                if candidate == state {
+                    self.pending = None;
+                    state
+                } else {
+                    let since_ms = match self.pending {
+                        Some((pending, since_ms)) if pending == candidate => since_ms,
+                        _ => timestamp_ms,
+                    };
+                    if timestamp_ms.saturating_sub(since_ms) >= self.min_dwell_ms {
+                        self.pending = None;
+                        candidate
+                    } else {
+                        self.pending = Some((candidate, since_ms));
+                        state
+                    }
+                }
+            }
+        };
+        self.state = Some(state);
+        state
+    }
+
+    /// Current state or `None` if there were no readings.
+    pub fn state(&self) -> Option<DaylightState> {
+        self.state
+    }
+
+    /// Threshold window outside of which the current state may be left,
+    /// in raw format for an integration time and gain.
+    ///
+    /// The window is open towards 0 at night and towards full scale in
+    /// direct sunlight. Without a state the window covers everything.
+    pub fn threshold_window(&self, it: IntegrationTime, gain: Gain) -> ThresholdWindow {
+        let state = match self.state {
+            Some(state) => state,
+            None => return ThresholdWindow::between_raw(0, u16::MAX),
+        };
+        let (low, high) = self.window(state);
+        let low = if state == DaylightState::Night {
+            0
+        } else {
+            calculate_raw_threshold_value(it, gain, low)
+        };
+        let high = if state == DaylightState::BrightSun {
+            u16::MAX
+        } else {
+            calculate_raw_threshold_value(it, gain, high)
+        };
+        ThresholdWindow::between_raw(low, high)
+    }
+
+    /// Forget the state so that the next reading sets it directly.
+    pub fn reset(&mut self) {
+        self.state = None;
+        self.pending = None;
+    }
+
+    fn classify(&self, lux: Lux) -> DaylightState {
+        let index = self
+            .boundaries
+            .iter()
+            .take_while(|boundary| lux > **boundary)
+            .count();
+        DaylightState::ALL[index]
+    }
+
+    /// Lux range within which the state is kept
+    fn window(&self, state: DaylightState) -> (Lux, Lux) {
+        let factor = self.hysteresis_percent / 100.0;
+        let index = state as usize;
+        let low = match index {
+            0 => Lux(0.0),
+            _ => Lux(self.boundaries[index - 1].0 * (1.0 - factor)),
+        };
+        let high = match self.boundaries.get(index) {
+            Some(boundary) => Lux(boundary.0 * (1.0 + factor)),
+            None => Lux(f32::INFINITY),
+        };
+        (low, high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DaylightState::*;
+
+    #[test]
+    fn classifies() {
+        let mut classifier = DaylightClassifier::default();
+        assert_eq!(None, classifier.state());
+        assert_eq!(Night, classifier.update(0, Lux(1.0)));
+        classifier.reset();
+        assert_eq!(Twilight, classifier.update(0, Lux(100.0)));
+        classifier.reset();
+        assert_eq!(Day, classifier.update(0, Lux(10000.0)));
+        classifier.reset();
+        assert_eq!(BrightSun, classifier.update(0, Lux(50000.0)));
+        assert_eq!(Some(BrightSun), classifier.state());
+    }
+
+    #[test]
+    fn applies_hysteresis() {
+        let mut classifier = DaylightClassifier::default();
+        assert_eq!(Twilight, classifier.update(0, Lux(300.0)));
+        assert_eq!(Twilight, classifier.update(1, Lux(430.0)));
+        assert_eq!(Day, classifier.update(2, Lux(450.0)));
+        assert_eq!(Day, classifier.update(3, Lux(370.0)));
+        assert_eq!(Twilight, classifier.update(4, Lux(350.0)));
+        assert_eq!(Night, classifier.update(5, Lux(1.0)));
+    }
+
+    #[test]
+    fn applies_min_dwell_time() {
+        let mut classifier = DaylightClassifier::default().with_min_dwell_ms(1000);
+        assert_eq!(Day, classifier.update(0, Lux(1000.0)));
+        assert_eq!(Day, classifier.update(100, Lux(100.0)));
+        assert_eq!(Day, classifier.update(500, Lux(1000.0)));
+        assert_eq!(Day, classifier.update(600, Lux(100.0)));
+        assert_eq!(Day, classifier.update(1500, Lux(100.0)));
+        assert_eq!(Twilight, classifier.update(1600, Lux(100.0)));
+    }
+
+    #[test]
+    fn threshold_window() {
+        let it = IntegrationTime::Ms100;
+        let gain = Gain::One;
+        let raw = |lux| calculate_raw_threshold_value(it, gain, Lux(lux));
+        let mut classifier = DaylightClassifier::default();
+        assert_eq!(
+            ThresholdWindow::between_raw(0, u16::MAX),
+            classifier.threshold_window(it, gain)
+        );
+        classifier.update(0, Lux(1.0));
+        assert_eq!(
+            ThresholdWindow::between_raw(0, raw(5.5)),
+            classifier.threshold_window(it, gain)
+        );
+        classifier.update(0, Lux(100.0));
+        assert_eq!(
+            ThresholdWindow::between_raw(raw(4.5), raw(440.0)),
+            classifier.threshold_window(it, gain)
+        );
+        classifier.update(0, Lux(50000.0));
+        assert_eq!(
+            ThresholdWindow::between_raw(raw(27000.0), u16::MAX),
+            classifier.threshold_window(it, gain)
+        );
+    }
+}
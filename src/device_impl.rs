@@ -1,7 +1,9 @@
 use super::correction::{correct_high_lux, get_lux_raw_conversion_factor};
 use crate::{
-    calculate_raw_threshold_value, Config, Error, FaultCount, Gain, IntegrationTime,
-    InterruptStatus, PowerSavingMode, SlaveAddr, Veml6030,
+    calculate_raw_threshold_value, max_lux, persistence_for_duration, refresh_time_ms,
+    resolution_lux_per_count, Config, Error, FaultCount, Gain, IntegrationTime, InterruptEvent,
+    InterruptStatus, InterruptStatusWithAls, Lux, Measurement, PersistenceConfig, PowerSavingMode,
+    SensorHealth, SlaveAddr, ThresholdWindow, TrackingWindow, Veml6030,
 };
 
 #[cfg(not(feature = "async"))]
@@ -30,6 +32,10 @@ impl BitFlags {
 }
 
 impl Config {
+    const POWER_ON: Config = Config {
+        bits: BitFlags::ALS_SD,
+    };
+
     fn with_high(self, mask: u16) -> Self {
         Config {
             bits: self.bits | mask,
@@ -48,11 +54,13 @@ impl<I2C> Veml6030<I2C> {
         Veml6030 {
             i2c,
             address: address.addr(),
-            config: Config {
-                bits: BitFlags::ALS_SD,
-            },
+            config: Config::POWER_ON,
             gain: Gain::One,
             it: IntegrationTime::Ms100,
+            high_threshold_lux: None,
+            low_threshold_lux: None,
+            tracking: None,
+            psm: None,
         }
     }
 
@@ -92,6 +100,8 @@ where
     }
 
     /// Set the integration time.
+    ///
+    /// Thresholds set in lux are recalculated and written again.
     pub async fn set_integration_time(&mut self, it: IntegrationTime) -> Result<(), Error<E>> {
         let mask = match it {
             IntegrationTime::Ms25 => 0b1100,
@@ -104,10 +114,12 @@ where
         let config = self.config.bits & !(0b1111 << 6) | (mask << 6);
         self.set_config(Config { bits: config }).await?;
         self.it = it;
-        Ok(())
+        self.update_lux_thresholds().await
     }
 
     /// Set the gain.
+    ///
+    /// Thresholds set in lux are recalculated and written again.
     pub async fn set_gain(&mut self, gain: Gain) -> Result<(), Error<E>> {
         let mask = match gain {
             Gain::One => 0,
@@ -118,7 +130,7 @@ where
         let config = self.config.bits & !(0b11 << 11) | (mask << 11);
         self.set_config(Config { bits: config }).await?;
         self.gain = gain;
-        Ok(())
+        self.update_lux_thresholds().await
     }
 
     /// Set the number of times a threshold crossing must happen consecutively
@@ -148,12 +160,16 @@ where
 
     /// Set the ALS high threshold in raw format
     pub async fn set_high_threshold_raw(&mut self, threshold: u16) -> Result<(), Error<E>> {
-        self.write_register(Register::ALS_WH, threshold).await
+        self.write_register(Register::ALS_WH, threshold).await?;
+        self.high_threshold_lux = None;
+        Ok(())
     }
 
     /// Set the ALS low threshold in raw format
     pub async fn set_low_threshold_raw(&mut self, threshold: u16) -> Result<(), Error<E>> {
-        self.write_register(Register::ALS_WL, threshold).await
+        self.write_register(Register::ALS_WL, threshold).await?;
+        self.low_threshold_lux = None;
+        Ok(())
     }
 
     /// Set the ALS high threshold in lux.
@@ -161,9 +177,14 @@ where
     /// For values higher than 1000 lx and 1/4 or 1/8 gain,
     /// the inverse of the compensation formula is applied (this involves
     /// quite some math).
-    pub async fn set_high_threshold_lux(&mut self, lux: f32) -> Result<(), Error<E>> {
+    ///
+    /// The lux value is remembered and the threshold is written again
+    /// whenever the gain or integration time changes.
+    pub async fn set_high_threshold_lux(&mut self, lux: Lux) -> Result<(), Error<E>> {
         let raw = self.calculate_raw_threshold_value(lux);
-        self.write_register(Register::ALS_WH, raw).await
+        self.write_register(Register::ALS_WH, raw).await?;
+        self.high_threshold_lux = Some(lux);
+        Ok(())
     }
 
     /// Set the ALS low threshold in lux.
@@ -171,9 +192,119 @@ where
     /// For values higher than 1000 lx and 1/4 or 1/8 gain,
     /// the inverse of the compensation formula is applied (this involves
     /// quite some math).
-    pub async fn set_low_threshold_lux(&mut self, lux: f32) -> Result<(), Error<E>> {
+    ///
+    /// The lux value is remembered and the threshold is written again
+    /// whenever the gain or integration time changes.
+    pub async fn set_low_threshold_lux(&mut self, lux: Lux) -> Result<(), Error<E>> {
         let raw = self.calculate_raw_threshold_value(lux);
-        self.write_register(Register::ALS_WL, raw).await
+        self.write_register(Register::ALS_WL, raw).await?;
+        self.low_threshold_lux = Some(lux);
+        Ok(())
+    }
+
+    /// Set the ALS low and high thresholds.
+    ///
+    /// Lux values are compensated if necessary. Returns
+    /// `Error::InvalidInputData` without writing anything if the low
+    /// threshold is not lower than the high threshold after conversion
+    /// to raw values.
+    ///
+    /// If the new high threshold is below the current low threshold, the low
+    /// threshold is written first, otherwise the high threshold is. This way
+    /// the device never holds a low threshold above the high one, which
+    /// could flag a spurious event. Should writing the second threshold
+    /// fail, the first one is restored so that the device keeps its previous
+    /// window.
+    ///
+    /// Lux thresholds are remembered and written again whenever the gain or
+    /// integration time changes.
+    pub async fn set_threshold_window(&mut self, window: ThresholdWindow) -> Result<(), Error<E>> {
+        let (low, high) = window.to_raw(self.it, self.gain);
+        if low >= high {
+            return Err(Error::InvalidInputData);
+        }
+        let previous_low = self.read_register(Register::ALS_WL).await?;
+        if high < previous_low {
+            self.write_register(Register::ALS_WL, low).await?;
+            if let Err(e) = self.write_register(Register::ALS_WH, high).await {
+                let _ = self.write_register(Register::ALS_WL, previous_low).await;
+                return Err(e);
+            }
+        } else {
+            let previous_high = self.read_register(Register::ALS_WH).await?;
+            self.write_register(Register::ALS_WH, high).await?;
+            if let Err(e) = self.write_register(Register::ALS_WL, low).await {
+                let _ = self.write_register(Register::ALS_WH, previous_high).await;
+                return Err(e);
+            }
+        }
+        match window {
+            ThresholdWindow::Lux { low, high } => {
+                self.low_threshold_lux = Some(low);
+                self.high_threshold_lux = Some(high);
+            }
+            ThresholdWindow::Raw { .. } => {
+                self.low_threshold_lux = None;
+                self.high_threshold_lux = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Enable threshold tracking.
+    ///
+    /// The threshold window is centred around the current lux value and
+    /// centred again every time the interrupt status read with
+    /// [`read_interrupt_status()`](#method.read_interrupt_status) flags
+    /// a threshold event. This way interrupts are only generated on
+    /// significant changes.
+    ///
+    /// The window always extends at least one count below and above the
+    /// ALS value so that it does not collapse at low light levels.
+    /// See also [`TrackingWindow::with_min_width()`].
+    pub async fn enable_threshold_tracking(
+        &mut self,
+        tracking: TrackingWindow,
+    ) -> Result<(), Error<E>> {
+        let raw_als = self.read_register(Register::ALS).await?;
+        self.recentre_tracking_window(tracking, raw_als).await?;
+        self.tracking = Some(tracking);
+        Ok(())
+    }
+
+    /// Disable threshold tracking.
+    ///
+    /// The current thresholds are kept.
+    pub fn disable_threshold_tracking(&mut self) {
+        self.tracking = None;
+    }
+
+    async fn recentre_tracking_window(
+        &mut self,
+        tracking: TrackingWindow,
+        raw_als: u16,
+    ) -> Result<(), Error<E>> {
+        let lux = self.convert_raw_als_to_lux(raw_als);
+        let (low, high) = tracking.around(lux).to_raw(self.it, self.gain);
+        let low = low.min(raw_als.saturating_sub(1));
+        let high = high.max(raw_als.saturating_add(1));
+        self.set_threshold_window(ThresholdWindow::between_raw(low, high))
+            .await?;
+        self.low_threshold_lux = Some(self.convert_raw_als_to_lux(low));
+        self.high_threshold_lux = Some(self.convert_raw_als_to_lux(high));
+        Ok(())
+    }
+
+    async fn update_lux_thresholds(&mut self) -> Result<(), Error<E>> {
+        if let Some(lux) = self.high_threshold_lux {
+            let raw = self.calculate_raw_threshold_value(lux);
+            self.write_register(Register::ALS_WH, raw).await?;
+        }
+        if let Some(lux) = self.low_threshold_lux {
+            let raw = self.calculate_raw_threshold_value(lux);
+            self.write_register(Register::ALS_WL, raw).await?;
+        }
+        Ok(())
     }
 
     /// Calculate raw value for threshold applying compensation if necessary.
@@ -184,25 +315,125 @@ where
     /// For values higher than 1000 lx and 1/4 or 1/8 gain, the inverse of the
     /// compensation formula is applied. This involves quite some math so it
     /// may be interesting to calculate the threshold values ahead of time.
-    pub fn calculate_raw_threshold_value(&self, lux: f32) -> u16 {
+    pub fn calculate_raw_threshold_value(&self, lux: Lux) -> u16 {
         calculate_raw_threshold_value(self.it, self.gain, lux)
     }
 
+    /// Lux value corresponding to one ALS count with the configured
+    /// integration time and gain.
+    ///
+    /// This is the resolution without compensation. For values higher than
+    /// 1000 lx and 1/4 or 1/8 gain, the compensation formula makes the
+    /// effective step between consecutive counts larger.
+    pub fn resolution_lux_per_count(&self) -> f32 {
+        resolution_lux_per_count(self.it, self.gain)
+    }
+
+    /// Maximum measurable lux value with the configured integration time
+    /// and gain.
+    ///
+    /// See [`max_lux()`](fn.max_lux.html).
+    pub fn max_lux(&self) -> Lux {
+        max_lux(self.it, self.gain)
+    }
+
     /// Enable the power-saving mode
     pub async fn enable_power_saving(&mut self, psm: PowerSavingMode) -> Result<(), Error<E>> {
-        let mask = match psm {
-            PowerSavingMode::One => 0,
-            PowerSavingMode::Two => 1,
-            PowerSavingMode::Three => 2,
-            PowerSavingMode::Four => 3,
-        };
-        let value = BitFlags::PSM_EN | (mask << 1);
-        self.write_register(Register::PSM, value).await
+        self.write_register(Register::PSM, psm_register_value(Some(psm)))
+            .await?;
+        self.psm = Some(psm);
+        Ok(())
     }
 
     /// Disable the power-saving mode
     pub async fn disable_power_saving(&mut self) -> Result<(), Error<E>> {
-        self.write_register(Register::PSM, 0).await
+        self.write_register(Register::PSM, psm_register_value(None))
+            .await?;
+        self.psm = None;
+        Ok(())
+    }
+
+    /// Time between measurements in milliseconds with the configured
+    /// integration time and power-saving mode.
+    pub fn refresh_time_ms(&self) -> u32 {
+        refresh_time_ms(self.it, self.psm)
+    }
+
+    /// Set the fault count so that a threshold must be exceeded for at least
+    /// the given duration in milliseconds to trigger an interrupt.
+    ///
+    /// If necessary, a power-saving mode with a longer wait time and, as
+    /// a last resort, a longer integration time are configured as well.
+    /// See [`persistence_for_duration()`](fn.persistence_for_duration.html).
+    ///
+    /// Returns the applied configuration including the achieved duration or
+    /// `Error::InvalidInputData` if the duration cannot be achieved.
+    pub async fn set_persistence_duration(
+        &mut self,
+        duration_ms: u32,
+    ) -> Result<PersistenceConfig, Error<E>> {
+        let config = persistence_for_duration(duration_ms, self.it, self.psm)
+            .ok_or(Error::InvalidInputData)?;
+        self.set_fault_count(config.fault_count).await?;
+        if config.it != self.it {
+            self.set_integration_time(config.it).await?;
+        }
+        if config.psm != self.psm {
+            if let Some(psm) = config.psm {
+                self.enable_power_saving(psm).await?;
+            }
+        }
+        Ok(config)
+    }
+
+    /// Check whether the device configuration matches the one set through
+    /// the driver.
+    ///
+    /// This reads the ALS_CONF and PSM registers. If the device was
+    /// power-cycled or reset independently of the driver, for example due
+    /// to a brownout, the registers are back at their power-on defaults and
+    /// the device is shut down. This is reported as `SensorHealth::Reset`
+    /// unless the driver configuration is the power-on default as well.
+    pub async fn check_health(&mut self) -> Result<SensorHealth, Error<E>> {
+        let config = self.read_register(Register::ALS_CONF).await?;
+        let psm = self.read_register(Register::PSM).await?;
+        let expected_psm = psm_register_value(self.psm);
+        Ok(if config == self.config.bits && psm == expected_psm {
+            SensorHealth::Ok
+        } else if config == Config::POWER_ON.bits && psm == psm_register_value(None) {
+            SensorHealth::Reset
+        } else {
+            SensorHealth::Mismatch
+        })
+    }
+
+    /// Write the configuration set through the driver to the device again.
+    ///
+    /// This restores the thresholds set in lux, the PSM register and, last,
+    /// the ALS_CONF register, so that the device is only powered on and
+    /// interrupts are only enabled once the rest is in place. Thresholds set
+    /// in raw format are not remembered and must be set again by the
+    /// application.
+    pub async fn restore_configuration(&mut self) -> Result<(), Error<E>> {
+        self.update_lux_thresholds().await?;
+        self.write_register(Register::PSM, psm_register_value(self.psm))
+            .await?;
+        self.write_register(Register::ALS_CONF, self.config.bits)
+            .await
+    }
+
+    /// Check the health of the device and restore the configuration if it
+    /// does not match.
+    ///
+    /// Returns the health found before restoring.
+    /// See [`check_health()`](#method.check_health) and
+    /// [`restore_configuration()`](#method.restore_configuration).
+    pub async fn check_health_and_restore(&mut self) -> Result<SensorHealth, Error<E>> {
+        let health = self.check_health().await?;
+        if health != SensorHealth::Ok {
+            self.restore_configuration().await?;
+        }
+        Ok(health)
     }
 
     async fn set_config(&mut self, config: Config) -> Result<(), Error<E>> {
@@ -221,9 +452,57 @@ where
     /// Read whether an interrupt has occurred.
     ///
     /// Note that the interrupt status is updated at the same rate as the
-    /// measurements. Once triggered, flags will stay true until a measurement
-    /// is taken which does not exceed the threshold.
+    /// measurements. Reading the interrupt status clears it and releases the
+    /// INT pin. The flags are set again by the next measurements exceeding
+    /// the threshold as many times as configured as fault count. To find out
+    /// how far the threshold was exceeded, read the status together with the
+    /// ALS value using
+    /// [`read_interrupt_status_with_als()`](#method.read_interrupt_status_with_als).
+    ///
+    /// If threshold tracking is enabled and a threshold event is flagged,
+    /// the threshold window is centred around the current lux value.
     pub async fn read_interrupt_status(&mut self) -> Result<InterruptStatus, Error<E>> {
+        let status = self.read_interrupt_flags().await?;
+        if let (Some(tracking), Some(_)) = (self.tracking, status.event()) {
+            let raw_als = self.read_register(Register::ALS).await?;
+            self.recentre_tracking_window(tracking, raw_als).await?;
+        }
+        Ok(status)
+    }
+
+    /// Read the interrupt status as an [`InterruptEvent`], if any.
+    ///
+    /// See [`read_interrupt_status()`](#method.read_interrupt_status).
+    pub async fn read_interrupt_event(&mut self) -> Result<Option<InterruptEvent>, Error<E>> {
+        Ok(self.read_interrupt_status().await?.event())
+    }
+
+    /// Read the interrupt status and the ALS measurement back-to-back.
+    ///
+    /// Since reading the interrupt status clears it, this allows for
+    /// getting the value that caused an event together with it.
+    /// The ALS value may belong to a later measurement if one lands
+    /// between both reads.
+    ///
+    /// If threshold tracking is enabled and a threshold event is flagged,
+    /// the threshold window is centred around the lux value read.
+    pub async fn read_interrupt_status_with_als(
+        &mut self,
+    ) -> Result<InterruptStatusWithAls, Error<E>> {
+        let status = self.read_interrupt_flags().await?;
+        let raw_als = self.read_register(Register::ALS).await?;
+        let lux = self.convert_raw_als_to_lux(raw_als);
+        if let (Some(tracking), Some(_)) = (self.tracking, status.event()) {
+            self.recentre_tracking_window(tracking, raw_als).await?;
+        }
+        Ok(InterruptStatusWithAls {
+            status,
+            raw_als,
+            lux,
+        })
+    }
+
+    async fn read_interrupt_flags(&mut self) -> Result<InterruptStatus, Error<E>> {
         let data = self.read_register(Register::ALS_INT).await?;
         Ok(InterruptStatus {
             was_too_low: (data & BitFlags::INT_TH_LOW) != 0,
@@ -241,7 +520,7 @@ where
     /// For values higher than 1000 lx and 1/4 or 1/8 gain,
     /// the following compensation formula is applied:
     /// `lux = 6.0135e-13*(lux^4) - 9.3924e-9*(lux^3) + 8.1488e-5*(lux^2) + 1.0023*lux`
-    pub async fn read_lux(&mut self) -> Result<f32, Error<E>> {
+    pub async fn read_lux(&mut self) -> Result<Lux, Error<E>> {
         let raw = self.read_register(Register::ALS).await?;
         Ok(self.convert_raw_als_to_lux(raw))
     }
@@ -254,15 +533,77 @@ where
     /// For values higher than 1000 lx and 1/4 or 1/8 gain,
     /// the following compensation formula is applied:
     /// `lux = 6.0135e-13*(lux^4) - 9.3924e-9*(lux^3) + 8.1488e-5*(lux^2) + 1.0023*lux`
-    pub fn convert_raw_als_to_lux(&self, raw_als: u16) -> f32 {
+    pub fn convert_raw_als_to_lux(&self, raw_als: u16) -> Lux {
         convert_raw_als_to_lux(self.it, self.gain, raw_als)
     }
 
+    /// Read the ALS high threshold in raw format
+    pub async fn read_high_threshold_raw(&mut self) -> Result<u16, Error<E>> {
+        self.read_register(Register::ALS_WH).await
+    }
+
+    /// Read the ALS low threshold in raw format
+    pub async fn read_low_threshold_raw(&mut self) -> Result<u16, Error<E>> {
+        self.read_register(Register::ALS_WL).await
+    }
+
+    /// Read the ALS high threshold converted to lux
+    ///
+    /// This takes into consideration the configured integration time and gain
+    /// and compensates the lux value if necessary.
+    pub async fn read_high_threshold_lux(&mut self) -> Result<Lux, Error<E>> {
+        let raw = self.read_register(Register::ALS_WH).await?;
+        Ok(self.convert_raw_als_to_lux(raw))
+    }
+
+    /// Read the ALS low threshold converted to lux
+    ///
+    /// This takes into consideration the configured integration time and gain
+    /// and compensates the lux value if necessary.
+    pub async fn read_low_threshold_lux(&mut self) -> Result<Lux, Error<E>> {
+        let raw = self.read_register(Register::ALS_WL).await?;
+        Ok(self.convert_raw_als_to_lux(raw))
+    }
+
     /// Read white channel measurement
     pub async fn read_white(&mut self) -> Result<u16, Error<E>> {
         self.read_register(Register::WHITE).await
     }
 
+    /// Read white channel measurement normalized to the integration time
+    /// and gain.
+    ///
+    /// See [`convert_raw_white_to_normalized()`](fn.convert_raw_white_to_normalized.html).
+    pub async fn read_white_normalized(&mut self) -> Result<f32, Error<E>> {
+        let raw = self.read_register(Register::WHITE).await?;
+        Ok(convert_raw_white_to_normalized(self.it, self.gain, raw))
+    }
+
+    /// Read the ALS and white channel measurements and the interrupt status.
+    ///
+    /// The registers are read back-to-back so that they belong to the same
+    /// measurement cycle as far as possible. The lux value is calculated
+    /// from the ALS measurement.
+    ///
+    /// Note that this clears the interrupt status. If threshold tracking is
+    /// enabled and a threshold event is flagged, the threshold window is
+    /// centred around the lux value read.
+    pub async fn read_all(&mut self) -> Result<Measurement, Error<E>> {
+        let raw_als = self.read_register(Register::ALS).await?;
+        let raw_white = self.read_register(Register::WHITE).await?;
+        let interrupt_status = self.read_interrupt_flags().await?;
+        let lux = self.convert_raw_als_to_lux(raw_als);
+        if let (Some(tracking), Some(_)) = (self.tracking, interrupt_status.event()) {
+            self.recentre_tracking_window(tracking, raw_als).await?;
+        }
+        Ok(Measurement {
+            raw_als,
+            raw_white,
+            lux,
+            interrupt_status,
+        })
+    }
+
     async fn read_register(&mut self, register: u8) -> Result<u16, Error<E>> {
         let mut data = [0; 2];
         self.i2c
@@ -273,17 +614,38 @@ where
     }
 }
 
+fn psm_register_value(psm: Option<PowerSavingMode>) -> u16 {
+    let mask = match psm {
+        None => return 0,
+        Some(PowerSavingMode::One) => 0,
+        Some(PowerSavingMode::Two) => 1,
+        Some(PowerSavingMode::Three) => 2,
+        Some(PowerSavingMode::Four) => 3,
+    };
+    BitFlags::PSM_EN | (mask << 1)
+}
+
 /// Calculate lux value for a raw ALS measurement.
 ///
 /// For values higher than 1000 lx and 1/4 or 1/8 gain,
 /// the following compensation formula is applied:
 /// `lux = 6.0135e-13*(lux^4) - 9.3924e-9*(lux^3) + 8.1488e-5*(lux^2) + 1.0023*lux`
-pub fn convert_raw_als_to_lux(it: IntegrationTime, gain: Gain, raw_als: u16) -> f32 {
+pub fn convert_raw_als_to_lux(it: IntegrationTime, gain: Gain, raw_als: u16) -> Lux {
     let factor = get_lux_raw_conversion_factor(it, gain);
     let lux = f64::from(raw_als) * f64::from(factor);
     if (gain == Gain::OneQuarter || gain == Gain::OneEighth) && lux > 1000.0 {
-        correct_high_lux(lux) as f32
+        Lux(correct_high_lux(lux) as f32)
     } else {
-        lux as f32
+        Lux(lux as f32)
     }
 }
+
+/// Normalize a raw white channel measurement to the integration time and gain.
+///
+/// The count is scaled with the same factor used to convert ALS counts to lux
+/// so that white channel values measured with different configurations can be
+/// compared. Note that the result is not a lux value since the white channel
+/// has a wider spectral response, and no compensation is applied.
+pub fn convert_raw_white_to_normalized(it: IntegrationTime, gain: Gain, raw_white: u16) -> f32 {
+    (f64::from(raw_white) * f64::from(get_lux_raw_conversion_factor(it, gain))) as f32
+}
@@ -0,0 +1,199 @@
+use crate::{Error, Measurement};
+
+/// Condition of the sensor derived from its readings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorCondition {
+    /// No suspicious pattern found.
+    Ok,
+    /// The ALS value has not changed for an implausibly long time.
+    Stuck,
+    /// Both channels have been 0 for an implausibly long time. The device
+    /// may be dead, shut down or covered.
+    StuckAtZero,
+    /// The ALS value has been at full scale for an implausibly long time.
+    /// The device may be saturated or the data corrupted.
+    StuckAtFullScale,
+    /// The white channel is much lower than the ALS channel, which is not
+    /// physically plausible. The sensor may be dirty or damaged.
+    Inconsistent,
+    /// Communication with the device failed repeatedly.
+    Disconnected,
+}
+
+/// Diagnostics for suspicious sensor data
+///
+/// Feed it with every fresh reading taken while the device is enabled as
+/// well as with every error. Only I²C errors count towards a disconnected
+/// sensor and a successful reading resets their count.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorDiagnostics {
+    stuck_ms: u64,
+    max_als_white_ratio: f32,
+    min_counts: u16,
+    max_consecutive_errors: u32,
+    unchanged_since: Option<(u64, u16)>,
+    consecutive_errors: u32,
+    condition: SensorCondition,
+}
+
+impl SensorDiagnostics {
+    /// Create new diagnostics flagging readings unchanged for the given
+    /// time in milliseconds.
+    ///
+    /// The ALS to white channel ratio may be at most 2 from 100 ALS counts
+    /// on and 3 consecutive I²C errors mean a disconnected sensor.
+    pub fn new(stuck_ms: u64) -> Self {
+        SensorDiagnostics {
+            stuck_ms,
+            max_als_white_ratio: 2.0,
+            min_counts: 100,
+            max_consecutive_errors: 3,
+            unchanged_since: None,
+            consecutive_errors: 0,
+            condition: SensorCondition::Ok,
+        }
+    }
+
+    /// Set the maximum plausible ratio of ALS to white channel counts and
+    /// the minimum ALS counts from which it is checked.
+    pub fn with_max_als_white_ratio(self, ratio: f32, min_counts: u16) -> Self {
+        SensorDiagnostics {
+            max_als_white_ratio: ratio,
+            min_counts,
+            ..self
+        }
+    }
+
+    /// Set the number of consecutive I²C errors meaning a disconnected
+    /// sensor. A count of 0 is treated as 1.
+    pub fn with_max_consecutive_errors(self, count: u32) -> Self {
+        SensorDiagnostics {
+            max_consecutive_errors: count.max(1),
+            ..self
+        }
+    }
+
+    /// Process new raw ALS and white channel readings taken at the given
+    /// time in milliseconds and return the resulting condition.
+    pub fn update(&mut self, timestamp_ms: u64, raw_als: u16, raw_white: u16) -> SensorCondition {
+        self.consecutive_errors = 0;
+        let since_ms = match self.unchanged_since {
+            Some((since_ms, previous)) if previous == raw_als => since_ms,
+            _ => timestamp_ms,
+        };
+        self.unchanged_since = Some((since_ms, raw_als));
+        let is_stuck = timestamp_ms.saturating_sub(since_ms) >= self.stuck_ms;
+        self.condition = if is_stuck && raw_als == 0 && raw_white == 0 {
+            SensorCondition::StuckAtZero
+        } else if is_stuck && raw_als == u16::MAX {
+            SensorCondition::StuckAtFullScale
+        } else if is_stuck {
+            SensorCondition::Stuck
+        } else if raw_als >= self.min_counts
+            && f32::from(raw_als) > f32::from(raw_white) * self.max_als_white_ratio
+        {
+            SensorCondition::Inconsistent
+        } else {
+            SensorCondition::Ok
+        };
+        self.condition
+    }
+
+    /// Process a measurement taken at the given time in milliseconds.
+    ///
+    /// See [`read_all()`](struct.Veml6030.html#method.read_all).
+    pub fn update_measurement(
+        &mut self,
+        timestamp_ms: u64,
+        measurement: &Measurement,
+    ) -> SensorCondition {
+        self.update(timestamp_ms, measurement.raw_als, measurement.raw_white)
+    }
+
+    /// Process an error and return the resulting condition.
+    pub fn record_error<E>(&mut self, error: &Error<E>) -> SensorCondition {
+        if let Error::I2C(_) = error {
+            self.consecutive_errors = self.consecutive_errors.saturating_add(1);
+            if self.consecutive_errors >= self.max_consecutive_errors {
+                self.condition = SensorCondition::Disconnected;
+            }
+        }
+        self.condition
+    }
+
+    /// Current condition.
+    pub fn condition(&self) -> SensorCondition {
+        self.condition
+    }
+
+    /// Forget all previous readings and errors.
+    pub fn reset(&mut self) {
+        self.unchanged_since = None;
+        self.consecutive_errors = 0;
+        self.condition = SensorCondition::Ok;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changing_readings_are_ok() {
+        let mut diagnostics = SensorDiagnostics::new(1000);
+        assert_eq!(SensorCondition::Ok, diagnostics.update(0, 100, 120));
+        assert_eq!(SensorCondition::Ok, diagnostics.update(600, 101, 120));
+        assert_eq!(SensorCondition::Ok, diagnostics.update(1200, 100, 120));
+    }
+
+    #[test]
+    fn detects_stuck_values() {
+        let mut diagnostics = SensorDiagnostics::new(1000);
+        assert_eq!(SensorCondition::Ok, diagnostics.update(0, 100, 120));
+        assert_eq!(SensorCondition::Ok, diagnostics.update(999, 100, 121));
+        assert_eq!(SensorCondition::Stuck, diagnostics.update(1000, 100, 120));
+        assert_eq!(SensorCondition::Ok, diagnostics.update(1100, 0, 0));
+        assert_eq!(SensorCondition::StuckAtZero, diagnostics.update(2100, 0, 0));
+        assert_eq!(
+            SensorCondition::Ok,
+            diagnostics.update(2200, u16::MAX, 0xFFFF)
+        );
+        assert_eq!(
+            SensorCondition::StuckAtFullScale,
+            diagnostics.update(3200, u16::MAX, 0xFFFF)
+        );
+    }
+
+    #[test]
+    fn detects_inconsistent_channels() {
+        let mut diagnostics = SensorDiagnostics::new(1000);
+        assert_eq!(
+            SensorCondition::Inconsistent,
+            diagnostics.update(0, 500, 200)
+        );
+        assert_eq!(SensorCondition::Ok, diagnostics.update(1, 50, 20));
+        let mut diagnostics = SensorDiagnostics::new(1000).with_max_als_white_ratio(3.0, 10);
+        assert_eq!(SensorCondition::Ok, diagnostics.update(0, 500, 200));
+        assert_eq!(SensorCondition::Inconsistent, diagnostics.update(1, 50, 10));
+    }
+
+    #[test]
+    fn detects_disconnection() {
+        let mut diagnostics = SensorDiagnostics::new(1000).with_max_consecutive_errors(2);
+        let i2c_error: Error<()> = Error::I2C(());
+        assert_eq!(SensorCondition::Ok, diagnostics.record_error(&i2c_error));
+        diagnostics.update(0, 100, 120);
+        assert_eq!(SensorCondition::Ok, diagnostics.record_error(&i2c_error));
+        assert_eq!(
+            SensorCondition::Ok,
+            diagnostics.record_error(&Error::<()>::InvalidInputData)
+        );
+        assert_eq!(
+            SensorCondition::Disconnected,
+            diagnostics.record_error(&i2c_error)
+        );
+        assert_eq!(SensorCondition::Disconnected, diagnostics.condition());
+        diagnostics.reset();
+        assert_eq!(SensorCondition::Ok, diagnostics.condition());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{Error, InterruptEvent, Lux, Veml6030};
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+/// Threshold event with the lux value measured when it was detected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThresholdEvent {
+    /// The measurement exceeded the high threshold.
+    TooHigh(Lux),
+    /// The measurement fell below the low threshold.
+    TooLow(Lux),
+    /// The measurement is back between the thresholds after having been
+    /// too high or too low.
+    BackInRange(Lux),
+}
+
+/// Source of threshold events
+///
+/// Polls the interrupt status and reports every transition between the
+/// "too low", "too high" and "in range" states. The poll interval should be
+/// at least the measurement refresh time so that each poll sees a new
+/// measurement.
+///
+/// The device only sets the threshold flags if interrupts are enabled, so
+/// [`enable_interrupts()`](struct.Veml6030.html#method.enable_interrupts)
+/// must be called beforehand. Otherwise no event is ever reported.
+///
+/// ```no_run
+/// # use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+/// use veml6030::{Lux, SlaveAddr, ThresholdEvent, ThresholdEvents, Veml6030};
+///
+/// # async fn run<I2C: I2c, D: DelayNs>(i2c: I2C, delay: D) {
+/// let mut sensor = Veml6030::new(i2c, SlaveAddr::default());
+/// sensor.set_high_threshold_lux(Lux(10000.0)).await.unwrap();
+/// sensor.set_low_threshold_lux(Lux(100.0)).await.unwrap();
+/// sensor.enable_interrupts().await.unwrap();
+/// sensor.enable().await.unwrap();
+/// let mut events = ThresholdEvents::new(sensor, delay, 100);
+/// loop {
+///     match events.next_event().await.unwrap() {
+///         ThresholdEvent::TooHigh(lux) => println!("too bright: {} lx", lux.0),
+///         ThresholdEvent::TooLow(lux) => println!("too dark: {} lx", lux.0),
+///         ThresholdEvent::BackInRange(lux) => println!("back in range: {} lx", lux.0),
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ThresholdEvents<I2C, D> {
+    sensor: Veml6030<I2C>,
+    delay: D,
+    poll_interval_ms: u32,
+    state: Option<InterruptEvent>,
+}
+
+impl<I2C, D> ThresholdEvents<I2C, D> {
+    /// Create a new event source polling the interrupt status every
+    /// `poll_interval_ms` milliseconds.
+    ///
+    /// The measurement is assumed to be in range initially.
+    pub fn new(sensor: Veml6030<I2C>, delay: D, poll_interval_ms: u32) -> Self {
+        ThresholdEvents {
+            sensor,
+            delay,
+            poll_interval_ms,
+            state: None,
+        }
+    }
+
+    /// Destroy the instance, return the device driver and the delay.
+    pub fn destroy(self) -> (Veml6030<I2C>, D) {
+        (self.sensor, self.delay)
+    }
+
+    /// Access the device driver.
+    pub fn sensor(&mut self) -> &mut Veml6030<I2C> {
+        &mut self.sensor
+    }
+}
+
+impl<I2C, D, E> ThresholdEvents<I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Wait for the next threshold event.
+    ///
+    /// Interrupts must be enabled on the device, otherwise this never
+    /// returns.
+    ///
+    /// The interrupt status is checked immediately and then after every
+    /// poll interval. A threshold flag different from the previous event
+    /// is reported together with the lux value read right after it.
+    ///
+    /// Since reading the interrupt status clears it, the absence of flags
+    /// does not mean that the measurement is back in range. After an
+    /// event, the ALS value is thus compared with the thresholds read from
+    /// the device on every poll without flags, and `BackInRange` is only
+    /// reported once it lies between them. The same comparison decides the
+    /// event when both flags latched since the previous poll.
+    pub async fn next_event(&mut self) -> Result<ThresholdEvent, Error<E>> {
+        loop {
+            let (state, raw) = match self.sensor.read_interrupt_status().await?.event() {
+                Some(InterruptEvent::Both) => {
+                    let (state, raw) = self.compare_with_thresholds().await?;
+                    (state, Some(raw))
+                }
+                None if self.state.is_some() => {
+                    let (state, raw) = self.compare_with_thresholds().await?;
+                    (state.and(self.state), Some(raw))
+                }
+                flagged => (flagged.or(self.state), None),
+            };
+            if state != self.state {
+                self.state = state;
+                let lux = match raw {
+                    Some(raw) => self.sensor.convert_raw_als_to_lux(raw),
+                    None => self.sensor.read_lux().await?,
+                };
+                return Ok(match state {
+                    Some(InterruptEvent::TooHigh) => ThresholdEvent::TooHigh(lux),
+                    Some(InterruptEvent::TooLow) => ThresholdEvent::TooLow(lux),
+                    _ => ThresholdEvent::BackInRange(lux),
+                });
+            }
+            self.delay.delay_ms(self.poll_interval_ms).await;
+        }
+    }
+
+    /// Read the ALS value and classify it against the device thresholds.
+    async fn compare_with_thresholds(&mut self) -> Result<(Option<InterruptEvent>, u16), Error<E>> {
+        let raw = self.sensor.read_raw().await?;
+        let low = self.sensor.read_low_threshold_raw().await?;
+        let high = self.sensor.read_high_threshold_raw().await?;
+        let state = if raw > high {
+            Some(InterruptEvent::TooHigh)
+        } else if raw < low {
+            Some(InterruptEvent::TooLow)
+        } else {
+            None
+        };
+        Ok((state, raw))
+    }
+}
@@ -0,0 +1,130 @@
+use crate::Lux;
+
+/// Camera settings for a correct exposure
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exposure {
+    /// Aperture as f-number
+    pub aperture: f32,
+    /// Shutter time in seconds
+    pub shutter_time_s: f32,
+    /// ISO speed
+    pub iso: f32,
+}
+
+/// Incident-light exposure meter
+///
+/// Uses the exposure equation `N² / t = E * S / C` with the f-number `N`,
+/// the shutter time `t` in seconds, the illuminance `E` in lux, the ISO
+/// speed `S` and the incident-light meter calibration constant `C`.
+/// Typical calibration constants are 250 for flat receptors and 320 to 340
+/// for hemispherical receptors.
+///
+/// With an illuminance of 0 lx, shutter times and ISO speeds are infinite.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExposureMeter {
+    calibration: f32,
+}
+
+impl Default for ExposureMeter {
+    /// Calibration constant 250
+    fn default() -> Self {
+        ExposureMeter::new(250.0)
+    }
+}
+
+impl ExposureMeter {
+    /// Full-stop f-numbers from f/1 to f/22
+    pub const STANDARD_APERTURES: [f32; 10] = [1.0, 1.4, 2.0, 2.8, 4.0, 5.6, 8.0, 11.0, 16.0, 22.0];
+
+    /// Create a new exposure meter with the given calibration constant.
+    pub fn new(calibration: f32) -> Self {
+        ExposureMeter { calibration }
+    }
+
+    /// Exposure value at ISO 100 (EV100) for a lux value.
+    pub fn exposure_value(&self, lux: Lux) -> f32 {
+        libm::log2f(lux.0 * 100.0 / self.calibration)
+    }
+
+    /// Shutter time in seconds for a lux value, aperture and ISO speed.
+    pub fn shutter_time_s(&self, lux: Lux, aperture: f32, iso: f32) -> f32 {
+        aperture * aperture * self.calibration / (lux.0 * iso)
+    }
+
+    /// Aperture as f-number for a lux value, shutter time and ISO speed.
+    pub fn aperture(&self, lux: Lux, shutter_time_s: f32, iso: f32) -> f32 {
+        libm::sqrtf(shutter_time_s * lux.0 * iso / self.calibration)
+    }
+
+    /// ISO speed for a lux value, aperture and shutter time.
+    pub fn iso(&self, lux: Lux, aperture: f32, shutter_time_s: f32) -> f32 {
+        aperture * aperture * self.calibration / (lux.0 * shutter_time_s)
+    }
+
+    /// Exposures for each of the [`STANDARD_APERTURES`](#associatedconstant.STANDARD_APERTURES)
+    /// at a lux value and ISO speed.
+    pub fn suggestions(&self, lux: Lux, iso: f32) -> impl Iterator<Item = Exposure> + '_ {
+        Self::STANDARD_APERTURES
+            .iter()
+            .map(move |&aperture| Exposure {
+                aperture,
+                shutter_time_s: self.shutter_time_s(lux, aperture, iso),
+                iso,
+            })
+    }
+}
+
+/// Exposure value at ISO 100 (EV100) for a lux value with the calibration
+/// constant 250.
+///
+/// See [`ExposureMeter`].
+pub fn lux_to_ev100(lux: Lux) -> f32 {
+    ExposureMeter::default().exposure_value(lux)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(expected: f32, actual: f32) {
+        assert!(
+            (expected - actual).abs() <= (expected.abs() * 1e-5).max(1e-6),
+            "{} != {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn exposure_value() {
+        assert_near(0.0, lux_to_ev100(Lux(2.5)));
+        assert_near(10.0, lux_to_ev100(Lux(2560.0)));
+        assert_near(10.0, ExposureMeter::new(320.0).exposure_value(Lux(3276.8)));
+    }
+
+    #[test]
+    fn exposure_equation() {
+        let meter = ExposureMeter::default();
+        let lux = Lux(2560.0);
+        assert_near(1.0 / 102.4, meter.shutter_time_s(lux, 1.0, 10.0));
+        assert_near(1.0 / 64.0, meter.shutter_time_s(lux, 8.0, 400.0));
+        assert_near(8.0, meter.aperture(lux, 1.0 / 64.0, 400.0));
+        assert_near(400.0, meter.iso(lux, 8.0, 1.0 / 64.0));
+    }
+
+    #[test]
+    fn suggestions() {
+        let meter = ExposureMeter::default();
+        let lux = Lux(2500.0);
+        let mut count = 0;
+        for exposure in meter.suggestions(lux, 100.0) {
+            assert_eq!(100.0, exposure.iso);
+            assert_near(
+                exposure.aperture,
+                meter.aperture(lux, exposure.shutter_time_s, 100.0),
+            );
+            count += 1;
+        }
+        assert_eq!(ExposureMeter::STANDARD_APERTURES.len(), count);
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{Error, Lux, Veml6030};
+use core::cmp::Ordering;
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Filter for a sequence of lux readings
+pub trait LuxFilter {
+    /// Process a new reading and return the filtered value.
+    fn update(&mut self, lux: Lux) -> Lux;
+
+    /// Forget all previous readings.
+    fn reset(&mut self);
+}
+
+/// Last `N` readings
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct History<const N: usize> {
+    values: [f32; N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> History<N> {
+    const fn new() -> Self {
+        History {
+            values: [0.0; N],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    fn push(&mut self, lux: Lux) {
+        if N != 0 {
+            self.values[self.next] = lux.0;
+            self.next = (self.next + 1) % N;
+            self.len = (self.len + 1).min(N);
+        }
+    }
+
+    /// Stored readings in no particular order
+    fn values(&self) -> &[f32] {
+        &self.values[..self.len]
+    }
+}
+
+/// Mean of the last `N` readings
+///
+/// Until `N` readings are available, the mean of the available ones is
+/// returned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovingAverage<const N: usize> {
+    history: History<N>,
+}
+
+impl<const N: usize> MovingAverage<N> {
+    /// Create a new moving average filter.
+    pub const fn new() -> Self {
+        MovingAverage {
+            history: History::new(),
+        }
+    }
+}
+
+impl<const N: usize> Default for MovingAverage<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> LuxFilter for MovingAverage<N> {
+    fn update(&mut self, lux: Lux) -> Lux {
+        self.history.push(lux);
+        let values = self.history.values();
+        if values.is_empty() {
+            return lux;
+        }
+        Lux(values.iter().sum::<f32>() / values.len() as f32)
+    }
+
+    fn reset(&mut self) {
+        self.history = History::new();
+    }
+}
+
+/// Exponential smoothing of the readings
+///
+/// Each reading moves the filtered value by the factor `alpha` (0 to 1)
+/// towards it. The first reading is taken as it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialSmoothing {
+    alpha: f32,
+    value: Option<f32>,
+}
+
+impl ExponentialSmoothing {
+    /// Create a new exponential smoothing filter.
+    ///
+    /// The smoothing factor is limited to the range 0 to 1. Lower values
+    /// smooth more.
+    pub fn new(alpha: f32) -> Self {
+        ExponentialSmoothing {
+            alpha: alpha.clamp(0.0, 1.0),
+            value: None,
+        }
+    }
+}
+
+impl LuxFilter for ExponentialSmoothing {
+    fn update(&mut self, lux: Lux) -> Lux {
+        let value = match self.value {
+            Some(value) => value + self.alpha * (lux.0 - value),
+            None => lux.0,
+        };
+        self.value = Some(value);
+        Lux(value)
+    }
+
+    fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+/// Median of the last `N` readings
+///
+/// Removes single outliers completely. With an even number of readings,
+/// the mean of the two middle values is returned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Median<const N: usize> {
+    history: History<N>,
+}
+
+impl<const N: usize> Median<N> {
+    /// Create a new median filter.
+    pub const fn new() -> Self {
+        Median {
+            history: History::new(),
+        }
+    }
+}
+
+impl<const N: usize> Default for Median<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> LuxFilter for Median<N> {
+    fn update(&mut self, lux: Lux) -> Lux {
+        self.history.push(lux);
+        let len = self.history.len;
+        if len == 0 {
+            return lux;
+        }
+        let mut sorted = self.history.values;
+        let sorted = &mut sorted[..len];
+        sorted.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        if len % 2 == 1 {
+            Lux(sorted[len / 2])
+        } else {
+            Lux((sorted[len / 2 - 1] + sorted[len / 2]) / 2.0)
+        }
+    }
+
+    fn reset(&mut self) {
+        self.history = History::new();
+    }
+}
+
+/// Exponential smoothing that follows large steps immediately
+///
+/// Small changes are smoothed with the factor `alpha`. If a reading
+/// differs from the filtered value by more than the step threshold, the
+/// filtered value jumps to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adaptive {
+    smoothing: ExponentialSmoothing,
+    step_percent: f32,
+    min_step: Lux,
+}
+
+impl Adaptive {
+    /// Create a new adaptive filter.
+    ///
+    /// Readings differing from the filtered value by more than
+    /// `step_percent` percent of it are followed immediately.
+    pub fn new(alpha: f32, step_percent: f32) -> Self {
+        Adaptive {
+            smoothing: ExponentialSmoothing::new(alpha),
+            step_percent,
+            min_step: Lux(0.0),
+        }
+    }
+
+    /// Set the minimum absolute difference considered a step.
+    ///
+    /// This keeps noise at low light levels from being followed.
+    pub fn with_min_step(self, min_step: Lux) -> Self {
+        Adaptive { min_step, ..self }
+    }
+}
+
+impl LuxFilter for Adaptive {
+    fn update(&mut self, lux: Lux) -> Lux {
+        if let Some(value) = self.smoothing.value {
+            let step = (libm::fabsf(value) * self.step_percent / 100.0).max(self.min_step.0);
+            if libm::fabsf(lux.0 - value) > step {
+                self.smoothing.value = Some(lux.0);
+                return lux;
+            }
+        }
+        self.smoothing.update(lux)
+    }
+
+    fn reset(&mut self) {
+        self.smoothing.reset();
+    }
+}
+
+/// VEML6030 device driver paired with a filter for its lux readings
+#[derive(Debug)]
+pub struct Veml6030WithFilter<I2C, F> {
+    sensor: Veml6030<I2C>,
+    filter: F,
+}
+
+impl<I2C, F: LuxFilter> Veml6030WithFilter<I2C, F> {
+    /// Pair a device driver with a filter.
+    pub fn new(sensor: Veml6030<I2C>, filter: F) -> Self {
+        Veml6030WithFilter { sensor, filter }
+    }
+
+    /// Destroy the instance, return the device driver and the filter.
+    pub fn destroy(self) -> (Veml6030<I2C>, F) {
+        (self.sensor, self.filter)
+    }
+
+    /// Access the device driver.
+    ///
+    /// Consider resetting the filter after changing the configuration.
+    pub fn sensor(&mut self) -> &mut Veml6030<I2C> {
+        &mut self.sensor
+    }
+
+    /// Access the filter.
+    pub fn filter(&mut self) -> &mut F {
+        &mut self.filter
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Veml6030WithFilter",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, F, E> Veml6030WithFilter<I2C, F>
+where
+    I2C: AsyncI2c<Error = E>,
+    F: LuxFilter,
+{
+    /// Read the lux value and return it filtered.
+    ///
+    /// See [`Veml6030::read_lux()`](struct.Veml6030.html#method.read_lux).
+    pub async fn read_lux(&mut self) -> Result<Lux, Error<E>> {
+        let lux = self.sensor.read_lux().await?;
+        Ok(self.filter.update(lux))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run<F: LuxFilter>(filter: &mut F, values: &[f32]) -> f32 {
+        values.iter().fold(0.0, |_, lux| filter.update(Lux(*lux)).0)
+    }
+
+    #[test]
+    fn moving_average() {
+        let mut filter = MovingAverage::<3>::new();
+        assert_eq!(Lux(10.0), filter.update(Lux(10.0)));
+        assert_eq!(Lux(15.0), filter.update(Lux(20.0)));
+        assert_eq!(Lux(20.0), filter.update(Lux(30.0)));
+        assert_eq!(Lux(30.0), filter.update(Lux(40.0)));
+        filter.reset();
+        assert_eq!(Lux(5.0), filter.update(Lux(5.0)));
+    }
+
+    #[test]
+    fn exponential_smoothing() {
+        let mut filter = ExponentialSmoothing::new(0.5);
+        assert_eq!(15.0, run(&mut filter, &[10.0, 20.0]));
+        assert_eq!(Lux(17.5), filter.update(Lux(20.0)));
+        filter.reset();
+        assert_eq!(Lux(40.0), filter.update(Lux(40.0)));
+    }
+
+    #[test]
+    fn median_removes_outliers() {
+        let mut filter = Median::<3>::new();
+        assert_eq!(12.0, run(&mut filter, &[10.0, 1000.0, 12.0]));
+        assert_eq!(Lux(12.0), filter.update(Lux(11.0)));
+    }
+
+    #[test]
+    fn median_of_even_count() {
+        let mut filter = Median::<4>::new();
+        assert_eq!(15.0, run(&mut filter, &[10.0, 20.0]));
+    }
+
+    #[test]
+    fn adaptive_follows_steps() {
+        let mut filter = Adaptive::new(0.5, 20.0);
+        assert_eq!(105.0, run(&mut filter, &[100.0, 110.0]));
+        assert_eq!(Lux(500.0), filter.update(Lux(500.0)));
+    }
+
+    #[test]
+    fn adaptive_applies_min_step() {
+        let mut filter = Adaptive::new(0.5, 20.0).with_min_step(Lux(5.0));
+        assert_eq!(3.0, run(&mut filter, &[2.0, 4.0]));
+        assert_eq!(Lux(10.0), filter.update(Lux(10.0)));
+    }
+}
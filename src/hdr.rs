@@ -0,0 +1,115 @@
+use crate::{Error, Gain, IntegrationTime, Lux, Sensitivity, Veml6030};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+#[cfg(feature = "async")]
+use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, i2c::I2c as AsyncI2c};
+
+/// Sensitivity settings for high-dynamic-range measurements
+///
+/// See [`read_lux_hdr()`](struct.Veml6030.html#method.read_lux_hdr).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HdrConfig {
+    /// Setting used first for the best resolution at low light levels
+    pub sensitive: Sensitivity,
+    /// Setting used if the sensitive measurement saturates
+    pub insensitive: Sensitivity,
+    /// Raw ALS value from which a measurement is considered saturated
+    pub saturation_raw: u16,
+}
+
+impl Default for HdrConfig {
+    /// Gain 2 with 400 ms (0.0072 lx/count, up to about 470 lx) and
+    /// gain 1/8 with 25 ms (1.8432 lx/count, full range).
+    fn default() -> Self {
+        HdrConfig {
+            sensitive: Sensitivity::new(Gain::Two, IntegrationTime::Ms400),
+            insensitive: Sensitivity::new(Gain::OneEighth, IntegrationTime::Ms25),
+            saturation_raw: u16::MAX,
+        }
+    }
+}
+
+/// High-dynamic-range measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HdrMeasurement {
+    /// Measured lux value
+    pub lux: Lux,
+    /// Raw ALS value
+    pub raw_als: u16,
+    /// Gain and integration time used for the measurement
+    pub sensitivity: Sensitivity,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Veml6030",
+        idents(AsyncI2c(sync = "I2c"), AsyncDelayNs(sync = "DelayNs"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Veml6030<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Read the lux value with the best available resolution over the
+    /// full measurement range.
+    ///
+    /// A measurement is first taken with the sensitive setting. Only if it
+    /// saturates, a second measurement is taken with the insensitive
+    /// setting. After each change of setting, this waits for the refresh
+    /// time of the previous setting plus the refresh time of the new one so
+    /// that a complete measurement is available. Afterwards the original
+    /// gain and integration time are restored, waiting the same way, so
+    /// that the next measurement read is taken with the original setting.
+    ///
+    /// If an error occurs, the gain and integration time may remain changed.
+    pub async fn read_lux_hdr<D: AsyncDelayNs>(
+        &mut self,
+        config: &HdrConfig,
+        delay: &mut D,
+    ) -> Result<HdrMeasurement, Error<E>> {
+        let original = Sensitivity::new(self.gain, self.it);
+        let mut sensitivity = config.sensitive;
+        self.change_sensitivity(sensitivity, delay).await?;
+        let mut raw_als = self.read_raw().await?;
+        if raw_als >= config.saturation_raw {
+            sensitivity = config.insensitive;
+            self.change_sensitivity(sensitivity, delay).await?;
+            raw_als = self.read_raw().await?;
+        }
+        let lux = self.convert_raw_als_to_lux(raw_als);
+        self.change_sensitivity(original, delay).await?;
+        Ok(HdrMeasurement {
+            lux,
+            raw_als,
+            sensitivity,
+        })
+    }
+
+    async fn change_sensitivity<D: AsyncDelayNs>(
+        &mut self,
+        sensitivity: Sensitivity,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        if sensitivity != Sensitivity::new(self.gain, self.it) {
+            let previous_refresh_ms = self.refresh_time_ms();
+            self.set_sensitivity(sensitivity).await?;
+            delay
+                .delay_ms(previous_refresh_ms + self.refresh_time_ms())
+                .await;
+        }
+        Ok(())
+    }
+
+    async fn set_sensitivity(&mut self, sensitivity: Sensitivity) -> Result<(), Error<E>> {
+        if sensitivity.gain != self.gain {
+            self.set_gain(sensitivity.gain).await?;
+        }
+        if sensitivity.it != self.it {
+            self.set_integration_time(sensitivity.it).await?;
+        }
+        Ok(())
+    }
+}
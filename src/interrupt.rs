@@ -0,0 +1,90 @@
+use crate::{InterruptEvent, InterruptPinError, Veml6030};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::{delay::DelayNs, digital::InputPin, i2c::I2c};
+#[cfg(feature = "async")]
+use embedded_hal_async::{digital::Wait, i2c::I2c};
+
+/// VEML6030 device driver paired with its interrupt (INT) pin
+///
+/// The INT pin is active-low and stays low until the interrupt status is
+/// read.
+#[derive(Debug)]
+pub struct Veml6030WithInterrupt<I2C, INT> {
+    sensor: Veml6030<I2C>,
+    int: INT,
+}
+
+impl<I2C, INT> Veml6030WithInterrupt<I2C, INT> {
+    /// Pair a device driver with the input pin connected to its INT pin.
+    ///
+    /// Interrupts still need to be enabled and the thresholds configured.
+    pub fn new(sensor: Veml6030<I2C>, int: INT) -> Self {
+        Veml6030WithInterrupt { sensor, int }
+    }
+
+    /// Destroy the instance, return the device driver and the pin.
+    pub fn destroy(self) -> (Veml6030<I2C>, INT) {
+        (self.sensor, self.int)
+    }
+
+    /// Access the device driver.
+    pub fn sensor(&mut self) -> &mut Veml6030<I2C> {
+        &mut self.sensor
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<I2C, INT, E> Veml6030WithInterrupt<I2C, INT>
+where
+    I2C: I2c<Error = E>,
+    INT: InputPin,
+{
+    /// Wait until a threshold interrupt occurs and return it.
+    ///
+    /// This polls the INT pin every `poll_interval_us` microseconds until it
+    /// is low and then reads the interrupt status, which releases the pin.
+    /// If the pin is already low, the pending interrupt is returned
+    /// immediately. Should the status not flag any event, waiting continues.
+    pub fn wait_for_threshold_event<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        poll_interval_us: u32,
+    ) -> Result<InterruptEvent, InterruptPinError<E, INT::Error>> {
+        loop {
+            while !self.int.is_low().map_err(InterruptPinError::Pin)? {
+                delay.delay_us(poll_interval_us);
+            }
+            if let Some(event) = self.sensor.read_interrupt_status()?.event() {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C, INT, E> Veml6030WithInterrupt<I2C, INT>
+where
+    I2C: I2c<Error = E>,
+    INT: Wait,
+{
+    /// Wait until a threshold interrupt occurs and return it.
+    ///
+    /// This waits for the INT pin to be low and then reads the interrupt
+    /// status, which releases the pin. If the pin is already low, the
+    /// pending interrupt is returned immediately. Should the status not
+    /// flag any event, waiting continues.
+    pub async fn wait_for_threshold_event(
+        &mut self,
+    ) -> Result<InterruptEvent, InterruptPinError<E, INT::Error>> {
+        loop {
+            self.int
+                .wait_for_low()
+                .await
+                .map_err(InterruptPinError::Pin)?;
+            if let Some(event) = self.sensor.read_interrupt_status().await?.event() {
+                return Ok(event);
+            }
+        }
+    }
+}
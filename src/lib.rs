@@ -7,30 +7,80 @@
 //! - Enable/disable the device. See: [`enable()`].
 //! - Read the measured lux value. See: [`read_lux()`].
 //! - Read the white channel measurement. See: [`read_white()`].
+//! - Read the white channel measurement normalized to the configuration. See: [`read_white_normalized()`].
+//! - Smooth the lux readings with a moving average, exponential, median or adaptive filter. See: [`Veml6030WithFilter`].
+//! - Map the lux value to a display brightness. See: [`BacklightController`].
+//! - Accumulate light statistics and the daily light integral. See: [`LightStatistics`].
+//! - Classify the lux value as night, twilight, day or bright sun. See: [`DaylightClassifier`].
+//! - Detect sudden light changes and sensor obstruction. See: [`ChangeDetector`].
+//! - Calculate exposure values and camera settings from the lux value. See: [`ExposureMeter`].
+//! - Classify the light source from the ALS to white channel ratio. See: [`LightSourceClassifier`].
+//! - Read the lux value over the full range switching between two sensitivities. See: [`read_lux_hdr()`].
+//! - Read several fresh lux measurements and their statistics. See: [`read_lux_oversampled()`].
 //! - Read the measured ALS value in raw format. See: [`read_raw()`].
+//! - Read ALS, white channel and interrupt status in one call. See: [`read_all()`].
 //! - Calculate the compensated lux for a raw ALS value. See: [`convert_raw_als_to_lux()`].
 //! - Set the gain. See: [`set_gain()`].
 //! - Set the integration time. See: [`set_integration_time()`].
 //! - Set the fault count. See: [`set_fault_count()`].
+//! - Set the fault count from a debounce duration. See: [`set_persistence_duration()`].
+//! - Get the time between measurements. See: [`refresh_time_ms()`].
 //! - Enable/disable and configure power saving mode. See: [`enable_power_saving()`].
+//! - Detect a reset of the device, for example after a brownout, and restore the configuration. See: [`check_health()`].
+//! - Detect stuck, implausible or missing readings. See: [`SensorDiagnostics`].
 //! - Enable/disable interrupts. See: [`enable_interrupts()`].
 //! - Read the interrupt status. See: [`read_interrupt_status()`].
+//! - Read the interrupt status together with the ALS value. See: [`read_interrupt_status_with_als()`].
+//! - Wait for a threshold interrupt on the INT pin. See: [`wait_for_threshold_event()`].
+//! - Receive threshold events with the lux value (`async` only). See: [`ThresholdEvents`].
 //! - Set the high/low thresholds in lux or raw. See: [`set_high_threshold_lux()`].
+//! - Set the high and low thresholds together as a window. See: [`set_threshold_window()`].
+//! - Read the high/low thresholds back in lux or raw. See: [`read_high_threshold_lux()`].
+//! - Make the threshold window follow the lux value after each interrupt. See: [`enable_threshold_tracking()`].
+//! - Emulate the threshold interrupt with unlimited persistence on the host. See: [`ThresholdMonitor`].
 //! - Calculate the compensated raw threshold value ahead of time. See: [`calculate_raw_threshold_value()`].
+//! - Use typed lux values convertible to foot-candles. See: [`Lux`].
+//! - Get the resolution and maximum measurable lux of the configuration. See: [`max_lux()`].
+//! - Enumerate the gain and integration time combinations ordered by sensitivity. See: [`Sensitivity`].
 //!
 //! [`enable()`]: struct.Veml6030.html#method.enable
 //! [`read_lux()`]: struct.Veml6030.html#method.read_lux
 //! [`read_white()`]: struct.Veml6030.html#method.read_white
+//! [`read_white_normalized()`]: struct.Veml6030.html#method.read_white_normalized
+//! [`Veml6030WithFilter`]: struct.Veml6030WithFilter.html
+//! [`BacklightController`]: struct.BacklightController.html
+//! [`LightStatistics`]: struct.LightStatistics.html
+//! [`DaylightClassifier`]: struct.DaylightClassifier.html
+//! [`ChangeDetector`]: struct.ChangeDetector.html
+//! [`ExposureMeter`]: struct.ExposureMeter.html
+//! [`LightSourceClassifier`]: struct.LightSourceClassifier.html
+//! [`read_lux_hdr()`]: struct.Veml6030.html#method.read_lux_hdr
+//! [`read_lux_oversampled()`]: struct.Veml6030.html#method.read_lux_oversampled
 //! [`read_raw()`]: struct.Veml6030.html#method.read_raw
+//! [`read_all()`]: struct.Veml6030.html#method.read_all
 //! [`convert_raw_als_to_lux()`]: fn.convert_raw_als_to_lux.html
 //! [`set_gain()`]: struct.Veml6030.html#method.set_gain
 //! [`set_integration_time()`]: struct.Veml6030.html#method.set_integration_time
 //! [`set_fault_count()`]: struct.Veml6030.html#method.set_fault_count
+//! [`set_persistence_duration()`]: struct.Veml6030.html#method.set_persistence_duration
+//! [`refresh_time_ms()`]: struct.Veml6030.html#method.refresh_time_ms
 //! [`enable_power_saving()`]: struct.Veml6030.html#method.enable_power_saving
+//! [`check_health()`]: struct.Veml6030.html#method.check_health
+//! [`SensorDiagnostics`]: struct.SensorDiagnostics.html
 //! [`enable_interrupts()`]: struct.Veml6030.html#method.enable_interrupts
 //! [`read_interrupt_status()`]: struct.Veml6030.html#method.read_interrupt_status
+//! [`read_interrupt_status_with_als()`]: struct.Veml6030.html#method.read_interrupt_status_with_als
+//! [`wait_for_threshold_event()`]: struct.Veml6030WithInterrupt.html#method.wait_for_threshold_event
+//! [`ThresholdEvents`]: struct.ThresholdEvents.html
 //! [`set_high_threshold_lux()`]: struct.Veml6030.html#method.set_high_threshold_lux
+//! [`set_threshold_window()`]: struct.Veml6030.html#method.set_threshold_window
+//! [`read_high_threshold_lux()`]: struct.Veml6030.html#method.read_high_threshold_lux
+//! [`enable_threshold_tracking()`]: struct.Veml6030.html#method.enable_threshold_tracking
+//! [`ThresholdMonitor`]: struct.ThresholdMonitor.html
 //! [`calculate_raw_threshold_value()`]: fn.calculate_raw_threshold_value.html
+//! [`Lux`]: struct.Lux.html
+//! [`max_lux()`]: struct.Veml6030.html#method.max_lux
+//! [`Sensitivity`]: struct.Sensitivity.html
 //!
 //! [Introductory blog post](https://blog.eldruin.com/veml6030-ambient-light-sensor-driver-in-rust/)
 //!
@@ -76,6 +126,242 @@
 //! }
 //! ```
 //!
+//! ### Convert the lux value to other units
+//!
+//! Lux values are returned as a [`Lux`] type, which can be converted
+//! to foot-candles.
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let lux = sensor.read_lux().unwrap();
+//! println!("lux: {:2}, fc: {:2}", lux, lux.as_foot_candles());
+//! let value: f32 = lux.into();
+//! ```
+//!
+//! ### Read all channels at once
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! loop {
+//!     let m = sensor.read_all().unwrap();
+//!     println!("lux: {:2}, als: {}, white: {}", m.lux, m.raw_als, m.raw_white);
+//! }
+//! ```
+//!
+//! ### Measure from moonlight to direct sunlight
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use veml6030::{HdrConfig, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let config = HdrConfig::default();
+//! let measurement = sensor.read_lux_hdr(&config, &mut Delay).unwrap();
+//! println!("lux: {:2}", measurement.lux);
+//! ```
+//!
+//! ### Average several measurements
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use veml6030::{SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let stats = sensor.read_lux_oversampled(10, &mut Delay).unwrap();
+//! println!("lux: {:2} +/- {:2}", stats.mean, stats.std_dev);
+//! ```
+//!
+//! ### Smooth the lux readings
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{Adaptive, Lux, SlaveAddr, Veml6030, Veml6030WithFilter};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let filter = Adaptive::new(0.1, 30.0).with_min_step(Lux(5.0));
+//! let mut sensor = Veml6030WithFilter::new(sensor, filter);
+//! loop {
+//!     let lux = sensor.read_lux().unwrap();
+//!     println!("lux: {:2}", lux);
+//! }
+//! ```
+//!
+//! ### Control a display backlight
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{BacklightController, BrightnessCurve, Lux, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let curve = BrightnessCurve::Logarithmic {
+//!     min_lux: Lux(1.0),
+//!     max_lux: Lux(10000.0),
+//!     min_brightness: 0.05,
+//!     max_brightness: 1.0,
+//! };
+//! let mut backlight = BacklightController::new(curve)
+//!     .with_hysteresis(10.0)
+//!     .with_max_step(0.02);
+//! loop {
+//!     let brightness = backlight.update(sensor.read_lux().unwrap());
+//!     println!("brightness: {:.2}", brightness);
+//! }
+//! ```
+//!
+//! ### Calculate the daily light integral
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use std::time::{Duration, Instant};
+//! use veml6030::{LightStatistics, Lux, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let start = Instant::now();
+//! let mut stats = LightStatistics::new(Lux(10000.0));
+//! while start.elapsed() < Duration::from_secs(24 * 3600) {
+//!     let timestamp_ms = start.elapsed().as_millis() as u64;
+//!     stats.update(timestamp_ms, sensor.read_lux().unwrap());
+//!     std::thread::sleep(Duration::from_secs(60));
+//! }
+//! println!("DLI: {:.1} mol/m²/d", stats.daily_light_integral());
+//! ```
+//!
+//! ### Switch lighting at dusk and dawn
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use std::time::Instant;
+//! use veml6030::{DaylightClassifier, DaylightState, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let start = Instant::now();
+//! let mut classifier = DaylightClassifier::default().with_min_dwell_ms(60_000);
+//! loop {
+//!     let timestamp_ms = start.elapsed().as_millis() as u64;
+//!     let state = classifier.update(timestamp_ms, sensor.read_lux().unwrap());
+//!     let lights_on = state <= DaylightState::Twilight;
+//!     println!("lights on: {}", lights_on);
+//! }
+//! ```
+//!
+//! ### Detect a covered sensor
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{ChangeDetector, LightChange, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let mut detector = ChangeDetector::new(50.0);
+//! loop {
+//!     let als = sensor.read_raw().unwrap();
+//!     let white = sensor.read_white().unwrap();
+//!     if detector.update(als, white) == LightChange::Obstructed {
+//!         println!("sensor covered");
+//!     }
+//! }
+//! ```
+//!
+//! ### Use the sensor as a light meter
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{ExposureMeter, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let meter = ExposureMeter::default();
+//! let lux = sensor.read_lux().unwrap();
+//! println!("EV100: {:.1}", meter.exposure_value(lux));
+//! for exposure in meter.suggestions(lux, 100.0) {
+//!     println!("f/{} {:.4} s", exposure.aperture, exposure.shutter_time_s);
+//! }
+//! ```
+//!
+//! ### Recover from a brownout of the device
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{SensorHealth, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! loop {
+//!     if sensor.check_health_and_restore().unwrap() == SensorHealth::Reset {
+//!         println!("device was reset, configuration restored");
+//!     }
+//!     println!("lux: {:2}", sensor.read_lux().unwrap());
+//! }
+//! ```
+//!
+//! ### Report a dead or disconnected sensor
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use std::time::Instant;
+//! use veml6030::{SensorCondition, SensorDiagnostics, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let start = Instant::now();
+//! let mut diagnostics = SensorDiagnostics::new(3_600_000);
+//! loop {
+//!     let timestamp_ms = start.elapsed().as_millis() as u64;
+//!     let condition = match sensor.read_all() {
+//!         Ok(measurement) => diagnostics.update_measurement(timestamp_ms, &measurement),
+//!         Err(e) => diagnostics.record_error(&e),
+//!     };
+//!     if condition != SensorCondition::Ok {
+//!         println!("sensor condition: {:?}", condition);
+//!     }
+//! }
+//! ```
+//!
+//! ### Distinguish daylight from artificial light
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{LightSource, LightSourceClassifier, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let classifier = LightSourceClassifier::default();
+//! let measurement = sensor.read_all().unwrap();
+//! match classifier.classify_measurement(&measurement) {
+//!     LightSource::SunlightOrIncandescent => println!("daylight or incandescent"),
+//!     LightSource::FluorescentOrLed => println!("artificial light"),
+//!     LightSource::Mixed => println!("mixed"),
+//!     LightSource::Unknown => println!("too dark"),
+//! }
+//! ```
+//!
 //! ### Provide an alternative address
 //!
 //! ```no_run
@@ -117,37 +403,110 @@
 //! ```no_run
 //! use linux_embedded_hal::I2cdev;
 //! use veml6030::{
-//!     FaultCount, Gain, IntegrationTime, SlaveAddr, Veml6030
+//!     FaultCount, Gain, IntegrationTime, Lux, SlaveAddr, Veml6030
 //! };
 //!
 //! let dev = I2cdev::new("/dev/i2c-1").unwrap();
 //! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
 //! sensor.set_gain(Gain::OneQuarter).unwrap();
 //! // this will compensate the value automatically before setting it
-//! sensor.set_high_threshold_lux(10000.0).unwrap();
-//! sensor.set_low_threshold_lux(100.0).unwrap();
+//! sensor.set_high_threshold_lux(Lux(10000.0)).unwrap();
+//! sensor.set_low_threshold_lux(Lux(100.0)).unwrap();
 //! sensor.set_fault_count(FaultCount::Four).unwrap();
 //! sensor.enable_interrupts().unwrap();
 //! sensor.enable().unwrap();
 //! ```
 //!
+//! ### Set a threshold window around the current lux value
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{SlaveAddr, ThresholdWindow, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let lux = sensor.read_lux().unwrap();
+//! // interrupt when the lux value changes by more than 20 %
+//! sensor
+//!     .set_threshold_window(ThresholdWindow::around(lux, 20.0))
+//!     .unwrap();
+//! sensor.enable_interrupts().unwrap();
+//! ```
+//!
+//! ### Get interrupts only on significant relative changes
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{Lux, SlaveAddr, TrackingWindow, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! // interrupt on changes of more than 10 %, at least 2 lx apart
+//! let tracking = TrackingWindow::percent(10.0).with_min_width(Lux(2.0));
+//! sensor.enable_threshold_tracking(tracking).unwrap();
+//! sensor.enable_interrupts().unwrap();
+//! loop {
+//!     // the window is centred again whenever an interrupt is flagged
+//!     let status = sensor.read_interrupt_status().unwrap();
+//!     // ...
+//! }
+//! ```
+//!
+//! ### Monitor thresholds without the INT pin
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{Lux, SlaveAddr, ThresholdMonitor, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.enable().unwrap();
+//! let mut monitor = ThresholdMonitor::new(Lux(100.0), Lux(10000.0))
+//!     .with_persistence(20)
+//!     .with_hysteresis(Lux(50.0));
+//! loop {
+//!     let status = monitor.update(sensor.read_lux().unwrap());
+//!     if status.was_too_high {
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! ### Require a threshold to be exceeded for some time
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{Lux, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.set_high_threshold_lux(Lux(10000.0)).unwrap();
+//! // interrupt only if the threshold is exceeded for at least 2 s
+//! let config = sensor.set_persistence_duration(2000).unwrap();
+//! println!("actual duration: {} ms", config.duration_ms);
+//! sensor.enable_interrupts().unwrap();
+//! sensor.enable().unwrap();
+//! ```
+//!
 //! ### Precalculate and set compensated threshold values
 //!
 //! Using current device configuration
 //!
 //! ```no_run
 //! use linux_embedded_hal::I2cdev;
-//! use veml6030::{Gain, IntegrationTime, SlaveAddr, Veml6030};
+//! use veml6030::{Gain, IntegrationTime, Lux, SlaveAddr, Veml6030};
 //!
 //! let dev = I2cdev::new("/dev/i2c-1").unwrap();
 //! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
 //! sensor.set_gain(Gain::OneEighth).unwrap();
 //! sensor.set_integration_time(IntegrationTime::Ms200).unwrap();
-//! let high_th_raw = sensor.calculate_raw_threshold_value(10000.0);
+//! let high_th_raw = sensor.calculate_raw_threshold_value(Lux(10000.0));
 //! // ...
 //! sensor.set_high_threshold_raw(high_th_raw).unwrap();
 //! // this requires no compensation because the value is < 1000
-//! sensor.set_low_threshold_lux(100.0).unwrap();
+//! sensor.set_low_threshold_lux(Lux(100.0)).unwrap();
 //! ```
 //!
 //! ### Precalculate and set compensated threshold values
@@ -158,12 +517,12 @@
 //! use linux_embedded_hal::I2cdev;
 //! use veml6030::{
 //!     calculate_raw_threshold_value,
-//!     Gain, IntegrationTime, SlaveAddr, Veml6030
+//!     Gain, IntegrationTime, Lux, SlaveAddr, Veml6030
 //! };
 //!
 //! let gain = Gain::OneEighth;
 //! let it = IntegrationTime::Ms200;
-//! let high_th_raw = calculate_raw_threshold_value(it, gain, 10000.0);
+//! let high_th_raw = calculate_raw_threshold_value(it, gain, Lux(10000.0));
 //! let dev = I2cdev::new("/dev/i2c-1").unwrap();
 //! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
 //! sensor.set_gain(gain).unwrap();
@@ -171,7 +530,7 @@
 //! // ...
 //! sensor.set_high_threshold_raw(high_th_raw).unwrap();
 //! // this requires no compensation because the value is < 1000
-//! sensor.set_low_threshold_lux(100.0).unwrap();
+//! sensor.set_low_threshold_lux(Lux(100.0)).unwrap();
 //! sensor.enable_interrupts().unwrap();
 //! sensor.enable().unwrap();
 //! ```
@@ -196,6 +555,88 @@
 //! }
 //! ```
 //!
+//! ### Read interrupt events together with the ALS value
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{InterruptEvent, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! // ...
+//! loop {
+//!     let result = sensor.read_interrupt_status_with_als().unwrap();
+//!     match result.status.event() {
+//!         Some(InterruptEvent::TooHigh) => println!("too high: {} lx", result.lux),
+//!         Some(InterruptEvent::TooLow) => println!("too low: {} lx", result.lux),
+//!         Some(InterruptEvent::Both) => println!("too high and too low: {} lx", result.lux),
+//!         None => (),
+//!     }
+//! }
+//! ```
+//!
+//! ### Get the current measurement range
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{Gain, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.set_gain(Gain::OneQuarter).unwrap();
+//! println!(
+//!     "resolution: {} lx, max: {} lx",
+//!     sensor.resolution_lux_per_count(),
+//!     sensor.max_lux()
+//! );
+//! ```
+//!
+//! ### Select the gain and integration time for an expected lux value
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6030::{Lux, Sensitivity, SlaveAddr, Veml6030};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! let sensitivity = Sensitivity::best_for_lux(Lux(5000.0));
+//! println!(
+//!     "resolution: {} lx, max: {} lx",
+//!     sensitivity.resolution_lux_per_count(),
+//!     sensitivity.max_lux()
+//! );
+//! sensor.set_gain(sensitivity.gain).unwrap();
+//! sensor.set_integration_time(sensitivity.it).unwrap();
+//! sensor.enable().unwrap();
+//! ```
+//!
+//! ### Wait for threshold interrupts on the INT pin
+//!
+//! ```no_run
+//! use linux_embedded_hal::{CdevPin, Delay, I2cdev};
+//! # use linux_embedded_hal::gpio_cdev::{Chip, LineRequestFlags};
+//! use veml6030::{InterruptEvent, Lux, SlaveAddr, Veml6030, Veml6030WithInterrupt};
+//!
+//! # let mut chip = Chip::new("/dev/gpiochip0").unwrap();
+//! # let handle = chip.get_line(17).unwrap()
+//! #     .request(LineRequestFlags::INPUT, 0, "veml6030-int").unwrap();
+//! let int = CdevPin::new(handle).unwrap();
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Veml6030::new(dev, SlaveAddr::default());
+//! sensor.set_high_threshold_lux(Lux(10000.0)).unwrap();
+//! sensor.set_low_threshold_lux(Lux(100.0)).unwrap();
+//! sensor.enable_interrupts().unwrap();
+//! sensor.enable().unwrap();
+//! let mut sensor = Veml6030WithInterrupt::new(sensor, int);
+//! loop {
+//!     match sensor.wait_for_threshold_event(&mut Delay, 1000).unwrap() {
+//!         InterruptEvent::TooHigh => println!("too bright"),
+//!         InterruptEvent::TooLow => println!("too dark"),
+//!         InterruptEvent::Both => println!("too bright and too dark"),
+//!     }
+//! }
+//! ```
+//!
 //! ### Read the raw ALS measurement and convert to lux separately
 //!
 //! Using current device configuration
@@ -244,13 +685,50 @@
 #![deny(unsafe_code, missing_docs)]
 #![no_std]
 
+mod backlight;
+pub use crate::backlight::{BacklightController, BrightnessCurve};
+mod change;
+pub use crate::change::{ChangeDetector, LightChange};
 mod correction;
+mod daylight;
+pub use crate::daylight::{DaylightClassifier, DaylightState};
 mod device_impl;
-pub use crate::correction::calculate_raw_threshold_value;
-pub use crate::device_impl::convert_raw_als_to_lux;
+mod diagnostics;
+pub use crate::correction::{calculate_raw_threshold_value, max_lux, resolution_lux_per_count};
+pub use crate::device_impl::{convert_raw_als_to_lux, convert_raw_white_to_normalized};
+pub use crate::diagnostics::{SensorCondition, SensorDiagnostics};
+#[cfg(feature = "async")]
+mod events;
+#[cfg(feature = "async")]
+pub use crate::events::{ThresholdEvent, ThresholdEvents};
+mod exposure;
+pub use crate::exposure::{lux_to_ev100, Exposure, ExposureMeter};
+mod filter;
+pub use crate::filter::{
+    Adaptive, ExponentialSmoothing, LuxFilter, Median, MovingAverage, Veml6030WithFilter,
+};
+mod hdr;
+pub use crate::hdr::{HdrConfig, HdrMeasurement};
+mod interrupt;
+pub use crate::interrupt::Veml6030WithInterrupt;
+mod light_source;
+pub use crate::light_source::{als_white_ratio, LightSource, LightSourceClassifier};
+mod monitor;
+pub use crate::monitor::ThresholdMonitor;
+mod oversampling;
+pub use crate::oversampling::OversampledLux;
+mod sensitivity;
+pub use crate::sensitivity::Sensitivity;
+mod statistics;
+pub use crate::statistics::LightStatistics;
+mod threshold;
+mod timing;
+pub use crate::threshold::{ThresholdWindow, TrackingWidth, TrackingWindow};
+pub use crate::timing::{persistence_for_duration, refresh_time_ms, PersistenceConfig};
 mod types;
 pub use crate::types::{
-    Error, FaultCount, Gain, IntegrationTime, InterruptStatus, PowerSavingMode, SlaveAddr,
+    Error, FaultCount, Gain, IntegrationTime, InterruptEvent, InterruptPinError, InterruptStatus,
+    InterruptStatusWithAls, Lux, Measurement, PowerSavingMode, SensorHealth, SlaveAddr,
 };
 
 /// VEML6030 device driver
@@ -262,6 +740,10 @@ pub struct Veml6030<I2C> {
     config: Config,
     gain: Gain,
     it: IntegrationTime,
+    high_threshold_lux: Option<Lux>,
+    low_threshold_lux: Option<Lux>,
+    tracking: Option<TrackingWindow>,
+    psm: Option<PowerSavingMode>,
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
@@ -0,0 +1,121 @@
+use crate::Measurement;
+
+/// Light source category
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightSource {
+    /// Light with high infrared content such as sunlight or incandescent light
+    SunlightOrIncandescent,
+    /// Light with low infrared content such as fluorescent or LED light
+    FluorescentOrLed,
+    /// Ratio between both categories, for example a mix of daylight and
+    /// artificial light
+    Mixed,
+    /// Not enough signal to classify the light
+    Unknown,
+}
+
+/// Light source classifier based on the ratio of ALS to white channel counts
+///
+/// The white channel has a wider spectral response than the ALS channel,
+/// reaching further into the infrared. The ratio of ALS to white counts is
+/// thus lower for light with high infrared content. Since both channels are
+/// measured with the same gain and integration time, the ratio does not
+/// depend on the configuration.
+///
+/// The default boundaries are indicative. The actual ratios depend on the
+/// optical design (cover glass, apertures) and should be calibrated with
+/// known light sources.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightSourceClassifier {
+    /// Ratios up to this value are classified as sunlight or incandescent
+    pub high_ir_max_ratio: f32,
+    /// Ratios from this value on are classified as fluorescent or LED
+    pub low_ir_min_ratio: f32,
+    /// Minimum white channel count needed for a classification
+    pub min_white_count: u16,
+}
+
+impl Default for LightSourceClassifier {
+    fn default() -> Self {
+        LightSourceClassifier {
+            high_ir_max_ratio: 0.6,
+            low_ir_min_ratio: 0.9,
+            min_white_count: 100,
+        }
+    }
+}
+
+impl LightSourceClassifier {
+    /// Classify the light source from raw ALS and white channel counts.
+    pub fn classify(&self, raw_als: u16, raw_white: u16) -> LightSource {
+        if raw_white < self.min_white_count.max(1) {
+            return LightSource::Unknown;
+        }
+        let ratio = als_white_ratio(raw_als, raw_white);
+        if ratio <= self.high_ir_max_ratio {
+            LightSource::SunlightOrIncandescent
+        } else if ratio >= self.low_ir_min_ratio {
+            LightSource::FluorescentOrLed
+        } else {
+            LightSource::Mixed
+        }
+    }
+
+    /// Classify the light source of a measurement.
+    ///
+    /// See [`read_all()`](struct.Veml6030.html#method.read_all).
+    pub fn classify_measurement(&self, measurement: &Measurement) -> LightSource {
+        self.classify(measurement.raw_als, measurement.raw_white)
+    }
+}
+
+/// Ratio of raw ALS to raw white channel counts.
+///
+/// Returns infinity if the white channel count is 0.
+pub fn als_white_ratio(raw_als: u16, raw_white: u16) -> f32 {
+    f32::from(raw_als) / f32::from(raw_white)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_light_sources() {
+        let classifier = LightSourceClassifier::default();
+        assert_eq!(
+            LightSource::SunlightOrIncandescent,
+            classifier.classify(500, 1000)
+        );
+        assert_eq!(LightSource::Mixed, classifier.classify(750, 1000));
+        assert_eq!(
+            LightSource::FluorescentOrLed,
+            classifier.classify(950, 1000)
+        );
+    }
+
+    #[test]
+    fn needs_enough_signal() {
+        let classifier = LightSourceClassifier::default();
+        assert_eq!(LightSource::Unknown, classifier.classify(50, 99));
+        assert_eq!(
+            LightSource::Unknown,
+            LightSourceClassifier {
+                min_white_count: 0,
+                ..classifier
+            }
+            .classify(0, 0)
+        );
+    }
+
+    #[test]
+    fn boundaries_can_be_configured() {
+        let classifier = LightSourceClassifier {
+            high_ir_max_ratio: 0.3,
+            low_ir_min_ratio: 0.5,
+            min_white_count: 10,
+        };
+        assert_eq!(LightSource::Mixed, classifier.classify(40, 100));
+        assert_eq!(LightSource::FluorescentOrLed, classifier.classify(50, 100));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{InterruptStatus, Lux};
+
+/// Host-side threshold monitor
+///
+/// Applies high/low thresholds and persistence to lux readings on the host.
+/// This allows for threshold alarms when the INT pin is not available and
+/// the lux value is polled instead.
+///
+/// Unlike the device interrupt status, the flags are not latched until they
+/// are read: they follow the readings and are cleared by the next reading
+/// within the thresholds. Thus only the flag for the current side is set
+/// after a reading that is too high followed by one that is too low, whereas
+/// the device would report both.
+///
+/// In addition to what the device offers, the persistence count is not
+/// limited and a hysteresis can be configured: once a threshold has been
+/// exceeded, the flag stays set until the reading is back within the
+/// threshold by more than the hysteresis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThresholdMonitor {
+    low: Lux,
+    high: Lux,
+    hysteresis: Lux,
+    persistence: u32,
+    low_count: u32,
+    high_count: u32,
+    status: InterruptStatus,
+}
+
+impl ThresholdMonitor {
+    /// Create a new monitor with the given thresholds.
+    ///
+    /// Persistence is one reading and there is no hysteresis.
+    pub fn new(low: Lux, high: Lux) -> Self {
+        ThresholdMonitor {
+            low,
+            high,
+            hysteresis: Lux(0.0),
+            persistence: 1,
+            low_count: 0,
+            high_count: 0,
+            status: InterruptStatus {
+                was_too_low: false,
+                was_too_high: false,
+            },
+        }
+    }
+
+    /// Set the number of consecutive readings exceeding a threshold
+    /// necessary to set the flag. A count of 0 is treated as 1.
+    pub fn with_persistence(self, count: u32) -> Self {
+        ThresholdMonitor {
+            persistence: count.max(1),
+            ..self
+        }
+    }
+
+    /// Set the hysteresis needed to clear a flag again.
+    pub fn with_hysteresis(self, hysteresis: Lux) -> Self {
+        ThresholdMonitor { hysteresis, ..self }
+    }
+
+    /// Process a new reading and return the resulting status.
+    pub fn update(&mut self, lux: Lux) -> InterruptStatus {
+        self.high_count = if lux > self.high {
+            self.high_count.saturating_add(1)
+        } else {
+            0
+        };
+        self.low_count = if lux < self.low {
+            self.low_count.saturating_add(1)
+        } else {
+            0
+        };
+        self.status = InterruptStatus {
+            was_too_high: self.high_count >= self.persistence
+                || (self.status.was_too_high && lux.0 > self.high.0 - self.hysteresis.0),
+            was_too_low: self.low_count >= self.persistence
+                || (self.status.was_too_low && lux.0 < self.low.0 + self.hysteresis.0),
+        };
+        self.status
+    }
+
+    /// Return the current status.
+    pub fn status(&self) -> InterruptStatus {
+        self.status
+    }
+
+    /// Clear the flags and the consecutive reading counts.
+    pub fn reset(&mut self) {
+        *self = ThresholdMonitor::new(self.low, self.high)
+            .with_hysteresis(self.hysteresis)
+            .with_persistence(self.persistence);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(was_too_low: bool, was_too_high: bool) -> InterruptStatus {
+        InterruptStatus {
+            was_too_low,
+            was_too_high,
+        }
+    }
+
+    #[test]
+    fn flags_exceeded_thresholds() {
+        let mut monitor = ThresholdMonitor::new(Lux(10.0), Lux(100.0));
+        assert_eq!(status(false, false), monitor.update(Lux(50.0)));
+        assert_eq!(status(false, true), monitor.update(Lux(101.0)));
+        assert_eq!(status(false, false), monitor.update(Lux(100.0)));
+        assert_eq!(status(true, false), monitor.update(Lux(9.0)));
+        assert_eq!(status(false, false), monitor.update(Lux(10.0)));
+    }
+
+    #[test]
+    fn flags_follow_readings_without_latching() {
+        let mut monitor = ThresholdMonitor::new(Lux(10.0), Lux(100.0));
+        assert_eq!(status(false, true), monitor.update(Lux(101.0)));
+        assert_eq!(status(true, false), monitor.update(Lux(9.0)));
+        assert_eq!(status(false, false), monitor.update(Lux(50.0)));
+        assert_eq!(status(false, false), monitor.status());
+    }
+
+    #[test]
+    fn applies_persistence() {
+        let mut monitor = ThresholdMonitor::new(Lux(10.0), Lux(100.0)).with_persistence(12);
+        for _ in 0..11 {
+            assert_eq!(status(false, false), monitor.update(Lux(200.0)));
+        }
+        assert_eq!(status(false, true), monitor.update(Lux(200.0)));
+        assert_eq!(status(false, true), monitor.update(Lux(200.0)));
+        assert_eq!(status(false, false), monitor.update(Lux(50.0)));
+        assert_eq!(status(false, false), monitor.update(Lux(200.0)));
+    }
+
+    #[test]
+    fn applies_hysteresis() {
+        let mut monitor = ThresholdMonitor::new(Lux(10.0), Lux(100.0)).with_hysteresis(Lux(5.0));
+        assert_eq!(status(false, true), monitor.update(Lux(101.0)));
+        assert_eq!(status(false, true), monitor.update(Lux(96.0)));
+        assert_eq!(status(false, false), monitor.update(Lux(95.0)));
+        assert_eq!(status(false, false), monitor.update(Lux(99.0)));
+        assert_eq!(status(true, false), monitor.update(Lux(9.0)));
+        assert_eq!(status(true, false), monitor.update(Lux(14.0)));
+        assert_eq!(status(false, false), monitor.update(Lux(15.0)));
+    }
+
+    #[test]
+    fn can_reset() {
+        let mut monitor = ThresholdMonitor::new(Lux(10.0), Lux(100.0)).with_persistence(2);
+        monitor.update(Lux(200.0));
+        monitor.update(Lux(200.0));
+        monitor.reset();
+        assert_eq!(status(false, false), monitor.status());
+        assert_eq!(status(false, false), monitor.update(Lux(200.0)));
+    }
+}
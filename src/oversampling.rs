@@ -0,0 +1,131 @@
+use crate::{Error, Lux, Veml6030};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+#[cfg(feature = "async")]
+use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, i2c::I2c as AsyncI2c};
+
+/// Statistics of several consecutive lux measurements
+///
+/// See [`read_lux_oversampled()`](struct.Veml6030.html#method.read_lux_oversampled).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OversampledLux {
+    /// Mean lux value
+    pub mean: Lux,
+    /// Minimum lux value
+    pub min: Lux,
+    /// Maximum lux value
+    pub max: Lux,
+    /// Population standard deviation in lux
+    pub std_dev: f32,
+    /// Number of samples
+    pub samples: u16,
+}
+
+#[derive(Debug, Default)]
+struct Accumulator {
+    count: u16,
+    mean: f32,
+    m2: f32,
+    min: f32,
+    max: f32,
+}
+
+impl Accumulator {
+    fn add(&mut self, lux: Lux) {
+        if self.count == 0 {
+            self.min = lux.0;
+            self.max = lux.0;
+        } else {
+            self.min = self.min.min(lux.0);
+            self.max = self.max.max(lux.0);
+        }
+        self.count += 1;
+        let delta = lux.0 - self.mean;
+        self.mean += delta / f32::from(self.count);
+        self.m2 += delta * (lux.0 - self.mean);
+    }
+
+    fn result(&self) -> OversampledLux {
+        OversampledLux {
+            mean: Lux(self.mean),
+            min: Lux(self.min),
+            max: Lux(self.max),
+            std_dev: libm::sqrtf(self.m2 / f32::from(self.count)),
+            samples: self.count,
+        }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Veml6030",
+        idents(AsyncI2c(sync = "I2c"), AsyncDelayNs(sync = "DelayNs"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Veml6030<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Read `n` consecutive lux measurements and calculate their statistics.
+    ///
+    /// Before each measurement, including the first one, this waits for the
+    /// refresh time of the configured integration time and power-saving mode
+    /// so that every sample is a new measurement and none of them predates
+    /// the call.
+    /// See [`refresh_time_ms()`](#method.refresh_time_ms).
+    ///
+    /// Returns `Error::InvalidInputData` if `n` is 0.
+    pub async fn read_lux_oversampled<D: AsyncDelayNs>(
+        &mut self,
+        n: u16,
+        delay: &mut D,
+    ) -> Result<OversampledLux, Error<E>> {
+        if n == 0 {
+            return Err(Error::InvalidInputData);
+        }
+        let mut accumulator = Accumulator::default();
+        for _ in 0..n {
+            delay.delay_ms(self.refresh_time_ms()).await;
+            accumulator.add(self.read_lux().await?);
+        }
+        Ok(accumulator.result())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_sample() {
+        let mut accumulator = Accumulator::default();
+        accumulator.add(Lux(10.0));
+        assert_eq!(
+            OversampledLux {
+                mean: Lux(10.0),
+                min: Lux(10.0),
+                max: Lux(10.0),
+                std_dev: 0.0,
+                samples: 1,
+            },
+            accumulator.result()
+        );
+    }
+
+    #[test]
+    fn statistics() {
+        let mut accumulator = Accumulator::default();
+        for lux in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            accumulator.add(Lux(lux));
+        }
+        let result = accumulator.result();
+        assert_eq!(Lux(5.0), result.mean);
+        assert_eq!(Lux(2.0), result.min);
+        assert_eq!(Lux(9.0), result.max);
+        assert!((result.std_dev - 2.0).abs() < 1e-6);
+        assert_eq!(8, result.samples);
+    }
+}
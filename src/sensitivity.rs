@@ -0,0 +1,183 @@
+use crate::{max_lux, resolution_lux_per_count, Gain, IntegrationTime, Lux};
+
+/// Combination of gain and integration time
+///
+/// Together these determine the resolution and the measurable range of the
+/// device. All possible combinations are available in [`Sensitivity::ALL`]
+/// ordered by sensitivity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sensitivity {
+    /// Gain
+    pub gain: Gain,
+    /// Integration time
+    pub it: IntegrationTime,
+}
+
+impl Sensitivity {
+    /// All gain and integration time combinations ordered from the least
+    /// sensitive (largest range) to the most sensitive (finest resolution).
+    ///
+    /// Combinations with the same resolution have the same range and are
+    /// ordered by increasing gain.
+    pub const ALL: [Sensitivity; 24] = [
+        Sensitivity::new(Gain::OneEighth, IntegrationTime::Ms25),
+        Sensitivity::new(Gain::OneEighth, IntegrationTime::Ms50),
+        Sensitivity::new(Gain::OneQuarter, IntegrationTime::Ms25),
+        Sensitivity::new(Gain::OneEighth, IntegrationTime::Ms100),
+        Sensitivity::new(Gain::OneQuarter, IntegrationTime::Ms50),
+        Sensitivity::new(Gain::OneEighth, IntegrationTime::Ms200),
+        Sensitivity::new(Gain::OneQuarter, IntegrationTime::Ms100),
+        Sensitivity::new(Gain::One, IntegrationTime::Ms25),
+        Sensitivity::new(Gain::OneEighth, IntegrationTime::Ms400),
+        Sensitivity::new(Gain::OneQuarter, IntegrationTime::Ms200),
+        Sensitivity::new(Gain::One, IntegrationTime::Ms50),
+        Sensitivity::new(Gain::Two, IntegrationTime::Ms25),
+        Sensitivity::new(Gain::OneEighth, IntegrationTime::Ms800),
+        Sensitivity::new(Gain::OneQuarter, IntegrationTime::Ms400),
+        Sensitivity::new(Gain::One, IntegrationTime::Ms100),
+        Sensitivity::new(Gain::Two, IntegrationTime::Ms50),
+        Sensitivity::new(Gain::OneQuarter, IntegrationTime::Ms800),
+        Sensitivity::new(Gain::One, IntegrationTime::Ms200),
+        Sensitivity::new(Gain::Two, IntegrationTime::Ms100),
+        Sensitivity::new(Gain::One, IntegrationTime::Ms400),
+        Sensitivity::new(Gain::Two, IntegrationTime::Ms200),
+        Sensitivity::new(Gain::One, IntegrationTime::Ms800),
+        Sensitivity::new(Gain::Two, IntegrationTime::Ms400),
+        Sensitivity::new(Gain::Two, IntegrationTime::Ms800),
+    ];
+
+    /// Create a new gain and integration time combination.
+    pub const fn new(gain: Gain, it: IntegrationTime) -> Self {
+        Sensitivity { gain, it }
+    }
+
+    /// Lux value corresponding to one ALS count.
+    ///
+    /// See [`resolution_lux_per_count()`](fn.resolution_lux_per_count.html).
+    pub fn resolution_lux_per_count(&self) -> f32 {
+        resolution_lux_per_count(self.it, self.gain)
+    }
+
+    /// Maximum measurable lux value.
+    ///
+    /// See [`max_lux()`](fn.max_lux.html).
+    pub fn max_lux(&self) -> Lux {
+        max_lux(self.it, self.gain)
+    }
+
+    /// Next combination in [`Sensitivity::ALL`] with a higher sensitivity.
+    ///
+    /// Returns `None` if this is already the most sensitive combination.
+    pub fn next_more_sensitive(&self) -> Option<Sensitivity> {
+        Self::ALL.get(self.position() + 1).copied()
+    }
+
+    /// Next combination in [`Sensitivity::ALL`] with a lower sensitivity.
+    ///
+    /// Returns `None` if this is already the least sensitive combination.
+    pub fn next_less_sensitive(&self) -> Option<Sensitivity> {
+        self.position()
+            .checked_sub(1)
+            .map(|position| Self::ALL[position])
+    }
+
+    /// Most sensitive combination able to measure the given lux value.
+    ///
+    /// Among combinations with the same resolution, the one with the
+    /// highest gain and thus the shortest integration time is returned.
+    /// If the lux value exceeds the range of all combinations, the least
+    /// sensitive one is returned.
+    pub fn best_for_lux(lux: Lux) -> Sensitivity {
+        Self::ALL
+            .iter()
+            .rev()
+            .find(|sensitivity| sensitivity.max_lux() >= lux)
+            .copied()
+            .unwrap_or(Self::ALL[0])
+    }
+
+    fn position(&self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|sensitivity| sensitivity == self)
+            .unwrap_or(0)
+    }
+}
+
+impl Default for Sensitivity {
+    /// Power-on gain and integration time
+    fn default() -> Self {
+        Sensitivity::new(Gain::One, IntegrationTime::Ms100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gain_factor(gain: Gain) -> f32 {
+        match gain {
+            Gain::OneEighth => 0.125,
+            Gain::OneQuarter => 0.25,
+            Gain::One => 1.0,
+            Gain::Two => 2.0,
+        }
+    }
+
+    #[test]
+    fn all_combinations_are_unique() {
+        for (i, a) in Sensitivity::ALL.iter().enumerate() {
+            for b in &Sensitivity::ALL[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn ordered_by_sensitivity() {
+        for pair in Sensitivity::ALL.windows(2) {
+            let (less, more) = (pair[0], pair[1]);
+            let (res_less, res_more) = (
+                less.resolution_lux_per_count(),
+                more.resolution_lux_per_count(),
+            );
+            assert!(res_less >= res_more);
+            assert!(less.max_lux() >= more.max_lux());
+            if res_less == res_more {
+                assert_eq!(less.max_lux(), more.max_lux());
+                assert!(gain_factor(less.gain) < gain_factor(more.gain));
+            }
+        }
+    }
+
+    #[test]
+    fn can_navigate() {
+        let first = Sensitivity::ALL[0];
+        let last = Sensitivity::ALL[23];
+        assert_eq!(None, first.next_less_sensitive());
+        assert_eq!(None, last.next_more_sensitive());
+        assert_eq!(Some(Sensitivity::ALL[1]), first.next_more_sensitive());
+        assert_eq!(Some(Sensitivity::ALL[22]), last.next_less_sensitive());
+    }
+
+    #[test]
+    fn best_for_lux() {
+        assert_eq!(
+            Sensitivity::new(Gain::Two, IntegrationTime::Ms800),
+            Sensitivity::best_for_lux(Lux(100.0))
+        );
+        assert_eq!(
+            Sensitivity::new(Gain::Two, IntegrationTime::Ms100),
+            Sensitivity::best_for_lux(Lux(1000.0))
+        );
+        assert_eq!(
+            Sensitivity::new(Gain::OneEighth, IntegrationTime::Ms25),
+            Sensitivity::best_for_lux(Lux(100000.0))
+        );
+        assert_eq!(
+            Sensitivity::new(Gain::OneQuarter, IntegrationTime::Ms25),
+            Sensitivity::best_for_lux(Lux(50000.0))
+        );
+        assert_eq!(Sensitivity::ALL[0], Sensitivity::best_for_lux(Lux(1.0e12)));
+    }
+}
@@ -0,0 +1,190 @@
+use crate::Lux;
+
+/// Accumulator for statistics of timestamped lux readings
+///
+/// Each reading is assumed to hold until the next one. The time-weighted
+/// values thus only consider the intervals between readings.
+///
+/// The daily light integral (DLI) for horticulture is calculated from the
+/// photosynthetic photon flux density (PPFD), which is estimated from the
+/// lux value with a factor depending on the light source. The default
+/// factor 0.0185 µmol/m²/s per lux corresponds to sunlight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightStatistics {
+    threshold: Lux,
+    ppfd_per_lux: f32,
+    count: u32,
+    min: Lux,
+    max: Lux,
+    last: Option<(u64, Lux)>,
+    duration_ms: u64,
+    lux_ms: f64,
+    above_threshold_ms: u64,
+}
+
+impl LightStatistics {
+    /// Create a new accumulator measuring the time above the given lux
+    /// threshold.
+    pub fn new(threshold: Lux) -> Self {
+        LightStatistics {
+            threshold,
+            ppfd_per_lux: 0.0185,
+            count: 0,
+            min: Lux(0.0),
+            max: Lux(0.0),
+            last: None,
+            duration_ms: 0,
+            lux_ms: 0.0,
+            above_threshold_ms: 0,
+        }
+    }
+
+    /// Set the factor to estimate the PPFD in µmol/m²/s from the lux value.
+    pub fn with_ppfd_factor(self, ppfd_per_lux: f32) -> Self {
+        LightStatistics {
+            ppfd_per_lux,
+            ..self
+        }
+    }
+
+    /// Process a new reading taken at the given time in milliseconds.
+    ///
+    /// Timestamps older than the previous one count as no time elapsed.
+    pub fn update(&mut self, timestamp_ms: u64, lux: Lux) {
+        if let Some((last_ms, last_lux)) = self.last {
+            let elapsed_ms = timestamp_ms.saturating_sub(last_ms);
+            self.duration_ms += elapsed_ms;
+            self.lux_ms += f64::from(last_lux.0) * elapsed_ms as f64;
+            if last_lux > self.threshold {
+                self.above_threshold_ms += elapsed_ms;
+            }
+        }
+        if self.count == 0 || lux < self.min {
+            self.min = lux;
+        }
+        if self.count == 0 || lux > self.max {
+            self.max = lux;
+        }
+        self.count = self.count.saturating_add(1);
+        let timestamp_ms = match self.last {
+            Some((last_ms, _)) => timestamp_ms.max(last_ms),
+            None => timestamp_ms,
+        };
+        self.last = Some((timestamp_ms, lux));
+    }
+
+    /// Number of readings processed.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Minimum lux value or `None` if there were no readings.
+    pub fn min(&self) -> Option<Lux> {
+        self.last.map(|_| self.min)
+    }
+
+    /// Maximum lux value or `None` if there were no readings.
+    pub fn max(&self) -> Option<Lux> {
+        self.last.map(|_| self.max)
+    }
+
+    /// Time-weighted mean lux value or `None` if no time has elapsed.
+    pub fn mean(&self) -> Option<Lux> {
+        if self.duration_ms == 0 {
+            None
+        } else {
+            Some(Lux((self.lux_ms / self.duration_ms as f64) as f32))
+        }
+    }
+
+    /// Time covered by the readings in milliseconds.
+    pub fn duration_ms(&self) -> u64 {
+        self.duration_ms
+    }
+
+    /// Time with the lux value above the threshold in milliseconds.
+    pub fn time_above_threshold_ms(&self) -> u64 {
+        self.above_threshold_ms
+    }
+
+    /// Integrated light dose in lux-hours.
+    pub fn lux_hours(&self) -> f32 {
+        (self.lux_ms / 3_600_000.0) as f32
+    }
+
+    /// Integrated photosynthetic photon flux in mol/m².
+    ///
+    /// When the readings span a day, this is the daily light integral.
+    pub fn daily_light_integral(&self) -> f32 {
+        (self.lux_ms / 1000.0 * f64::from(self.ppfd_per_lux) / 1_000_000.0) as f32
+    }
+
+    /// Forget all readings, for example at the start of a new day.
+    pub fn reset(&mut self) {
+        *self = LightStatistics::new(self.threshold).with_ppfd_factor(self.ppfd_per_lux);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: u64 = 3_600_000;
+
+    #[test]
+    fn empty() {
+        let stats = LightStatistics::new(Lux(100.0));
+        assert_eq!(0, stats.count());
+        assert_eq!(None, stats.min());
+        assert_eq!(None, stats.max());
+        assert_eq!(None, stats.mean());
+        assert_eq!(0.0, stats.lux_hours());
+    }
+
+    #[test]
+    fn accumulates() {
+        let mut stats = LightStatistics::new(Lux(100.0));
+        stats.update(0, Lux(50.0));
+        stats.update(HOUR_MS, Lux(200.0));
+        stats.update(3 * HOUR_MS, Lux(10.0));
+        assert_eq!(3, stats.count());
+        assert_eq!(Some(Lux(10.0)), stats.min());
+        assert_eq!(Some(Lux(200.0)), stats.max());
+        assert_eq!(Some(Lux(150.0)), stats.mean());
+        assert_eq!(3 * HOUR_MS, stats.duration_ms());
+        assert_eq!(2 * HOUR_MS, stats.time_above_threshold_ms());
+        assert_eq!(450.0, stats.lux_hours());
+    }
+
+    #[test]
+    fn daily_light_integral() {
+        let mut stats = LightStatistics::new(Lux(0.0));
+        stats.update(0, Lux(10000.0));
+        stats.update(12 * HOUR_MS, Lux(0.0));
+        stats.update(24 * HOUR_MS, Lux(0.0));
+        let expected = 10000.0 * 0.0185 * 12.0 * 3600.0 / 1_000_000.0;
+        assert!((stats.daily_light_integral() - expected).abs() < 1e-4);
+        let mut stats = LightStatistics::new(Lux(0.0)).with_ppfd_factor(0.014);
+        stats.update(0, Lux(1000.0));
+        stats.update(1000, Lux(1000.0));
+        assert!((stats.daily_light_integral() - 1.4e-5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ignores_time_going_backwards() {
+        let mut stats = LightStatistics::new(Lux(0.0));
+        stats.update(1000, Lux(10.0));
+        stats.update(500, Lux(20.0));
+        stats.update(2000, Lux(20.0));
+        assert_eq!(1000, stats.duration_ms());
+        assert_eq!(Some(Lux(20.0)), stats.mean());
+    }
+
+    #[test]
+    fn reset_keeps_configuration() {
+        let mut stats = LightStatistics::new(Lux(5.0)).with_ppfd_factor(0.02);
+        stats.update(0, Lux(10.0));
+        stats.reset();
+        assert_eq!(LightStatistics::new(Lux(5.0)).with_ppfd_factor(0.02), stats);
+    }
+}
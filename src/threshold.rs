@@ -0,0 +1,185 @@
+use crate::{calculate_raw_threshold_value, Gain, IntegrationTime, Lux};
+
+/// ALS interrupt threshold window
+///
+/// An interrupt is generated when the measurement leaves the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThresholdWindow {
+    /// Thresholds in lux. These are compensated if necessary.
+    Lux {
+        /// Low threshold
+        low: Lux,
+        /// High threshold
+        high: Lux,
+    },
+    /// Thresholds in raw format
+    Raw {
+        /// Low threshold
+        low: u16,
+        /// High threshold
+        high: u16,
+    },
+}
+
+impl ThresholdWindow {
+    /// Create a window between two lux values.
+    pub fn between(low: Lux, high: Lux) -> Self {
+        ThresholdWindow::Lux { low, high }
+    }
+
+    /// Create a window between two raw values.
+    pub fn between_raw(low: u16, high: u16) -> Self {
+        ThresholdWindow::Raw { low, high }
+    }
+
+    /// Create a window extending the given percentage below and above
+    /// a lux value.
+    ///
+    /// The low threshold is limited to 0 lx.
+    pub fn around(lux: Lux, percent: f32) -> Self {
+        let delta = lux.0 * percent / 100.0;
+        ThresholdWindow::Lux {
+            low: Lux((lux.0 - delta).max(0.0)),
+            high: Lux(lux.0 + delta),
+        }
+    }
+
+    /// Calculate the raw `(low, high)` thresholds for an integration time
+    /// and gain, applying compensation if necessary.
+    pub fn to_raw(&self, it: IntegrationTime, gain: Gain) -> (u16, u16) {
+        match *self {
+            ThresholdWindow::Lux { low, high } => (
+                calculate_raw_threshold_value(it, gain, low),
+                calculate_raw_threshold_value(it, gain, high),
+            ),
+            ThresholdWindow::Raw { low, high } => (low, high),
+        }
+    }
+}
+
+/// Distance from the measured value to each threshold of a tracking window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackingWidth {
+    /// Percentage of the measured lux value
+    Percent(f32),
+    /// Fixed lux value
+    Absolute(Lux),
+}
+
+/// Threshold window that follows the measured lux value
+///
+/// After each threshold interrupt the window is centred again around the
+/// current lux value so that only significant changes generate interrupts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackingWindow {
+    /// Distance from the measured value to each threshold
+    pub width: TrackingWidth,
+    /// Minimum distance between the low and high thresholds
+    pub min_width: Lux,
+}
+
+impl TrackingWindow {
+    /// Create a tracking window extending the given percentage below and
+    /// above the measured value.
+    pub fn percent(percent: f32) -> Self {
+        TrackingWindow {
+            width: TrackingWidth::Percent(percent),
+            min_width: Lux(0.0),
+        }
+    }
+
+    /// Create a tracking window extending a fixed lux value below and above
+    /// the measured value.
+    pub fn absolute(lux: Lux) -> Self {
+        TrackingWindow {
+            width: TrackingWidth::Absolute(lux),
+            min_width: Lux(0.0),
+        }
+    }
+
+    /// Set the minimum distance between the low and high thresholds.
+    ///
+    /// This keeps percentage windows from collapsing at low light levels.
+    pub fn with_min_width(self, min_width: Lux) -> Self {
+        TrackingWindow { min_width, ..self }
+    }
+
+    /// Calculate the threshold window centred around a lux value.
+    ///
+    /// The low threshold is limited to 0 lx.
+    pub fn around(&self, lux: Lux) -> ThresholdWindow {
+        let delta = match self.width {
+            TrackingWidth::Percent(percent) => lux.0 * percent / 100.0,
+            TrackingWidth::Absolute(delta) => delta.0,
+        };
+        let delta = delta.max(self.min_width.0 / 2.0);
+        ThresholdWindow::Lux {
+            low: Lux((lux.0 - delta).max(0.0)),
+            high: Lux(lux.0 + delta),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn around() {
+        assert_eq!(
+            ThresholdWindow::between(Lux(90.0), Lux(110.0)),
+            ThresholdWindow::around(Lux(100.0), 10.0)
+        );
+    }
+
+    #[test]
+    fn around_limits_low_threshold() {
+        assert_eq!(
+            ThresholdWindow::between(Lux(0.0), Lux(300.0)),
+            ThresholdWindow::around(Lux(100.0), 200.0)
+        );
+    }
+
+    #[test]
+    fn tracking_percent() {
+        assert_eq!(
+            ThresholdWindow::between(Lux(80.0), Lux(120.0)),
+            TrackingWindow::percent(20.0).around(Lux(100.0))
+        );
+    }
+
+    #[test]
+    fn tracking_absolute() {
+        assert_eq!(
+            ThresholdWindow::between(Lux(95.0), Lux(105.0)),
+            TrackingWindow::absolute(Lux(5.0)).around(Lux(100.0))
+        );
+    }
+
+    #[test]
+    fn tracking_applies_min_width() {
+        let tracking = TrackingWindow::percent(10.0).with_min_width(Lux(4.0));
+        assert_eq!(
+            ThresholdWindow::between(Lux(8.0), Lux(12.0)),
+            tracking.around(Lux(10.0))
+        );
+        assert_eq!(
+            ThresholdWindow::between(Lux(90.0), Lux(110.0)),
+            tracking.around(Lux(100.0))
+        );
+    }
+
+    #[test]
+    fn raw_is_not_converted() {
+        let window = ThresholdWindow::between_raw(100, 200);
+        assert_eq!((100, 200), window.to_raw(IntegrationTime::Ms25, Gain::Two));
+    }
+
+    #[test]
+    fn lux_is_converted() {
+        let window = ThresholdWindow::between(Lux(57.6), Lux(115.2));
+        let (low, high) = window.to_raw(IntegrationTime::Ms100, Gain::One);
+        assert!((999..=1000).contains(&low));
+        assert!((1999..=2000).contains(&high));
+    }
+}
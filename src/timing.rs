@@ -0,0 +1,145 @@
+use crate::{FaultCount, IntegrationTime, PowerSavingMode};
+
+/// Time between measurements in milliseconds.
+///
+/// This is the integration time plus the power-saving mode wait time,
+/// if enabled.
+pub fn refresh_time_ms(it: IntegrationTime, psm: Option<PowerSavingMode>) -> u32 {
+    u32::from(it.as_ms()) + psm.map_or(0, |psm| psm.wait_time_ms())
+}
+
+/// Persistence configuration achieving a debounce duration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PersistenceConfig {
+    /// Fault count
+    pub fault_count: FaultCount,
+    /// Integration time
+    pub it: IntegrationTime,
+    /// Power-saving mode, `None` if disabled
+    pub psm: Option<PowerSavingMode>,
+    /// Achieved duration in milliseconds a threshold must be exceeded for to
+    /// trigger an interrupt
+    ///
+    /// This spans the fault count measurements, that is, the refresh time
+    /// between each of them plus the integration time of the last one.
+    pub duration_ms: u32,
+}
+
+const FAULT_COUNTS: [FaultCount; 4] = [
+    FaultCount::One,
+    FaultCount::Two,
+    FaultCount::Four,
+    FaultCount::Eight,
+];
+const INTEGRATION_TIMES: [IntegrationTime; 6] = [
+    IntegrationTime::Ms25,
+    IntegrationTime::Ms50,
+    IntegrationTime::Ms100,
+    IntegrationTime::Ms200,
+    IntegrationTime::Ms400,
+    IntegrationTime::Ms800,
+];
+const POWER_SAVING_MODES: [PowerSavingMode; 4] = [
+    PowerSavingMode::One,
+    PowerSavingMode::Two,
+    PowerSavingMode::Three,
+    PowerSavingMode::Four,
+];
+
+/// Select the persistence configuration so that a threshold must be exceeded
+/// for at least the given duration in milliseconds to trigger an interrupt.
+///
+/// The fault count is selected first while keeping the given integration
+/// time and power-saving mode. If that is not enough, a power-saving mode
+/// with a longer wait time and, as a last resort, a longer integration time
+/// are selected. Note that changing the integration time changes the
+/// resolution and range of the measurements.
+///
+/// Returns `None` if the duration cannot be achieved.
+pub fn persistence_for_duration(
+    duration_ms: u32,
+    it: IntegrationTime,
+    psm: Option<PowerSavingMode>,
+) -> Option<PersistenceConfig> {
+    let psm_wait_time = psm.map_or(0, |psm| psm.wait_time_ms());
+    let psm_candidates = core::iter::once(psm).chain(
+        POWER_SAVING_MODES
+            .iter()
+            .filter(move |mode| mode.wait_time_ms() > psm_wait_time)
+            .map(|mode| Some(*mode)),
+    );
+    INTEGRATION_TIMES
+        .iter()
+        .filter(|candidate| candidate.as_ms() >= it.as_ms())
+        .flat_map(|it| psm_candidates.clone().map(move |psm| (*it, psm)))
+        .flat_map(|(it, psm)| {
+            FAULT_COUNTS.iter().map(move |fc| PersistenceConfig {
+                fault_count: *fc,
+                it,
+                psm,
+                duration_ms: u32::from(fc.as_count() - 1) * refresh_time_ms(it, psm)
+                    + u32::from(it.as_ms()),
+            })
+        })
+        .find(|config| config.duration_ms >= duration_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_time() {
+        assert_eq!(100, refresh_time_ms(IntegrationTime::Ms100, None));
+        assert_eq!(
+            4800,
+            refresh_time_ms(IntegrationTime::Ms800, Some(PowerSavingMode::Four))
+        );
+    }
+
+    #[test]
+    fn selects_fault_count() {
+        let config = persistence_for_duration(300, IntegrationTime::Ms100, None).unwrap();
+        assert_eq!(
+            PersistenceConfig {
+                fault_count: FaultCount::Four,
+                it: IntegrationTime::Ms100,
+                psm: None,
+                duration_ms: 400,
+            },
+            config
+        );
+    }
+
+    #[test]
+    fn selects_power_saving_mode() {
+        let psm = Some(PowerSavingMode::One);
+        let config = persistence_for_duration(10_000, IntegrationTime::Ms100, psm).unwrap();
+        assert_eq!(
+            PersistenceConfig {
+                fault_count: FaultCount::Eight,
+                it: IntegrationTime::Ms100,
+                psm: Some(PowerSavingMode::Three),
+                duration_ms: 14_800,
+            },
+            config
+        );
+    }
+
+    #[test]
+    fn selects_integration_time() {
+        let config = persistence_for_duration(30_000, IntegrationTime::Ms25, None).unwrap();
+        assert_eq!(FaultCount::Eight, config.fault_count);
+        assert_eq!(IntegrationTime::Ms400, config.it);
+        assert_eq!(Some(PowerSavingMode::Four), config.psm);
+        assert_eq!(31_200, config.duration_ms);
+    }
+
+    #[test]
+    fn cannot_exceed_maximum() {
+        assert_eq!(
+            None,
+            persistence_for_duration(34_401, IntegrationTime::Ms25, None)
+        );
+    }
+}
@@ -3,6 +3,62 @@
 pub enum Error<E> {
     /// I²C bus error
     I2C(E),
+    /// Invalid input data provided
+    InvalidInputData,
+}
+
+/// Errors of the device driver paired with its interrupt pin
+///
+/// See [`Veml6030WithInterrupt`](struct.Veml6030WithInterrupt.html).
+#[derive(Debug)]
+pub enum InterruptPinError<E, PinE> {
+    /// Device driver error
+    Device(Error<E>),
+    /// Interrupt pin error
+    Pin(PinE),
+}
+
+impl<E, PinE> From<Error<E>> for InterruptPinError<E, PinE> {
+    fn from(error: Error<E>) -> Self {
+        InterruptPinError::Device(error)
+    }
+}
+
+/// Illuminance in lux
+///
+/// Used throughout the API to avoid mixing up lux values and raw ALS counts.
+///
+/// No conversion into [`uom`](https://docs.rs/uom) quantities is provided
+/// since `uom` has no illuminance quantity and lux must not be represented
+/// as a different physical quantity such as luminance.
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Lux(pub f32);
+
+/// Number of lux in one foot-candle
+const LUX_PER_FOOT_CANDLE: f32 = 10.763_91;
+
+impl Lux {
+    /// Create a lux value from foot-candles
+    pub fn from_foot_candles(fc: f32) -> Self {
+        Lux(fc * LUX_PER_FOOT_CANDLE)
+    }
+
+    /// Return the value in foot-candles
+    pub fn as_foot_candles(&self) -> f32 {
+        self.0 / LUX_PER_FOOT_CANDLE
+    }
+}
+
+impl From<Lux> for f32 {
+    fn from(lux: Lux) -> Self {
+        lux.0
+    }
+}
+
+impl core::fmt::Display for Lux {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(&self.0, f)
+    }
 }
 
 /// Integration time
@@ -70,6 +126,18 @@ pub enum FaultCount {
     Eight,
 }
 
+impl FaultCount {
+    /// Return the number of consecutive fault events
+    pub fn as_count(&self) -> u8 {
+        match self {
+            FaultCount::One => 1,
+            FaultCount::Two => 2,
+            FaultCount::Four => 4,
+            FaultCount::Eight => 8,
+        }
+    }
+}
+
 /// Power-saving mode
 ///
 /// This combined with the integration time determines the repetition rate
@@ -86,6 +154,18 @@ pub enum PowerSavingMode {
     Four,
 }
 
+impl PowerSavingMode {
+    /// Return the wait time between measurements in milliseconds
+    pub fn wait_time_ms(&self) -> u32 {
+        match self {
+            PowerSavingMode::One => 500,
+            PowerSavingMode::Two => 1000,
+            PowerSavingMode::Three => 2000,
+            PowerSavingMode::Four => 4000,
+        }
+    }
+}
+
 /// Interrupt status
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct InterruptStatus {
@@ -97,6 +177,72 @@ pub struct InterruptStatus {
     pub was_too_high: bool,
 }
 
+impl InterruptStatus {
+    /// Return the threshold event flagged in this status, if any.
+    ///
+    /// The flags latch until the status is read, so both of them are set if
+    /// the measurement was too high and then too low (or the other way
+    /// around) since the last read. This is reported as `Both`.
+    pub fn event(&self) -> Option<InterruptEvent> {
+        match (self.was_too_low, self.was_too_high) {
+            (false, false) => None,
+            (true, false) => Some(InterruptEvent::TooLow),
+            (false, true) => Some(InterruptEvent::TooHigh),
+            (true, true) => Some(InterruptEvent::Both),
+        }
+    }
+}
+
+/// Result of comparing the device configuration with the driver
+///
+/// See [`check_health()`](struct.Veml6030.html#method.check_health).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorHealth {
+    /// The device configuration matches the driver.
+    Ok,
+    /// The device is back at its power-on configuration, for example after
+    /// a brownout.
+    Reset,
+    /// The device configuration differs from the driver otherwise.
+    Mismatch,
+}
+
+/// Interrupt status together with the ALS measurement read right after it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InterruptStatusWithAls {
+    /// Interrupt status
+    pub status: InterruptStatus,
+    /// ALS measurement in raw format
+    pub raw_als: u16,
+    /// ALS measurement converted to lux
+    pub lux: Lux,
+}
+
+/// ALS and white channel measurements together with the interrupt status
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    /// ALS measurement in raw format
+    pub raw_als: u16,
+    /// White channel measurement in raw format
+    pub raw_white: u16,
+    /// ALS measurement converted to lux
+    pub lux: Lux,
+    /// Interrupt status
+    pub interrupt_status: InterruptStatus,
+}
+
+/// Threshold interrupt event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterruptEvent {
+    /// The measurement was lower than the low threshold.
+    TooLow,
+    /// The measurement was higher than the high threshold.
+    TooHigh,
+    /// The measurement was both lower than the low threshold and higher than
+    /// the high threshold since the interrupt status was last read.
+    Both,
+}
+
 /// Possible slave addresses
 #[derive(Debug, Clone, Copy)]
 pub enum SlaveAddr {
@@ -146,4 +292,34 @@ mod tests {
         assert_eq!(IntegrationTime::Ms800.as_ms(), 800);
         assert_eq!(IntegrationTime::Ms800.as_us(), 800_000);
     }
+
+    #[test]
+    fn fault_count_as_int() {
+        assert_eq!(FaultCount::One.as_count(), 1);
+        assert_eq!(FaultCount::Eight.as_count(), 8);
+    }
+
+    #[test]
+    fn psm_wait_time() {
+        assert_eq!(PowerSavingMode::One.wait_time_ms(), 500);
+        assert_eq!(PowerSavingMode::Four.wait_time_ms(), 4000);
+    }
+
+    #[test]
+    fn interrupt_status_event() {
+        let status = |was_too_low, was_too_high| InterruptStatus {
+            was_too_low,
+            was_too_high,
+        };
+        assert_eq!(None, status(false, false).event());
+        assert_eq!(Some(InterruptEvent::TooLow), status(true, false).event());
+        assert_eq!(Some(InterruptEvent::TooHigh), status(false, true).event());
+        assert_eq!(Some(InterruptEvent::Both), status(true, true).event());
+    }
+
+    #[test]
+    fn lux_foot_candles() {
+        assert_eq!(Lux(10.763_91), Lux::from_foot_candles(1.0));
+        assert_eq!(1.0, Lux(10.763_91).as_foot_candles());
+    }
 }
@@ -1,7 +1,18 @@
-use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::delay::{CheckedDelay, Transaction as DelayTrans};
+use embedded_hal_mock::eh1::{
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+};
 use veml6030::{
-    FaultCount as FC, Gain, IntegrationTime as IT, InterruptStatus, PowerSavingMode as PSM,
+    calculate_raw_threshold_value, convert_raw_als_to_lux, convert_raw_white_to_normalized,
+    max_lux, Error, FaultCount as FC, Gain, HdrConfig, HdrMeasurement, IntegrationTime as IT,
+    InterruptEvent, InterruptStatus, InterruptStatusWithAls, Lux, Measurement, Median,
+    PersistenceConfig, PowerSavingMode as PSM, SensorHealth, ThresholdWindow, TrackingWindow,
+    Veml6030WithFilter, Veml6030WithInterrupt,
 };
+#[cfg(feature = "async")]
+use veml6030::{ThresholdEvent, ThresholdEvents};
 
 mod common;
 use crate::common::{destroy, new, BitFlags as BF, Register as Reg, CFG_DEFAULT, DEV_ADDR};
@@ -183,6 +194,41 @@ get_test!(
 );
 get_test!(read_als_raw, read_raw, ALS, 0xABCD_u16, 0xABCD);
 get_test!(read_white, read_white, WHITE, 0xABCD_u16, 0xABCD);
+get_test!(
+    read_white_normalized,
+    read_white_normalized,
+    WHITE,
+    1000_u16,
+    convert_raw_white_to_normalized(IT::Ms100, Gain::One, 1000)
+);
+get_test!(
+    read_high_th_raw,
+    read_high_threshold_raw,
+    ALS_WH,
+    0xABCD_u16,
+    0xABCD
+);
+get_test!(
+    read_low_th_raw,
+    read_low_threshold_raw,
+    ALS_WL,
+    0xABCD_u16,
+    0xABCD
+);
+get_test!(
+    read_high_th_lux,
+    read_high_threshold_lux,
+    ALS_WH,
+    1000_u16,
+    convert_raw_als_to_lux(IT::Ms100, Gain::One, 1000)
+);
+get_test!(
+    read_low_th_lux,
+    read_low_threshold_lux,
+    ALS_WL,
+    1000_u16,
+    convert_raw_als_to_lux(IT::Ms100, Gain::One, 1000)
+);
 
 macro_rules! read_lux_test {
     ($name:ident, $it:ident, $gain:ident, $config1:expr, $config2:expr, $als:expr, $expected:expr) => {
@@ -211,7 +257,7 @@ macro_rules! read_lux_test {
             let mut sensor = new(&transactions);
             sensor.set_integration_time(IT::$it).await.unwrap();
             sensor.set_gain(Gain::$gain).await.unwrap();
-            let result = sensor.read_lux().await.unwrap();
+            let result = sensor.read_lux().await.unwrap().0;
             assert!($expected - 0.5 < result);
             assert!($expected + 0.5 > result);
             destroy(sensor);
@@ -297,8 +343,20 @@ set_test!(
     0xABCD_u16,
     0xABCD
 );
-set_test!(highth_lux, set_high_threshold_lux, ALS_WH, 1480_u16, 85.248);
-set_test!(low_th_lux, set_low_threshold_lux, ALS_WL, 1480_u16, 85.248);
+set_test!(
+    highth_lux,
+    set_high_threshold_lux,
+    ALS_WH,
+    1480_u16,
+    Lux(85.248)
+);
+set_test!(
+    low_th_lux,
+    set_low_threshold_lux,
+    ALS_WL,
+    1480_u16,
+    Lux(85.248)
+);
 
 macro_rules! set_th_test {
     ($name:ident, $method:ident, $register:ident) => {
@@ -329,7 +387,7 @@ macro_rules! set_th_test {
             let mut sensor = new(&transactions);
             sensor.set_integration_time(IT::Ms25).await.unwrap();
             sensor.set_gain(Gain::OneEighth).await.unwrap();
-            sensor.$method(3183.247).await.unwrap();
+            sensor.$method(Lux(3183.247)).await.unwrap();
             destroy(sensor);
         }
     };
@@ -345,3 +403,754 @@ set_th_test!(
     set_low_threshold_lux,
     ALS_WL
 );
+
+#[test]
+fn can_get_default_resolution_and_max_lux() {
+    let sensor = new(&[]);
+    assert_eq!(0.0576, sensor.resolution_lux_per_count());
+    assert!(Lux(3774.0) < sensor.max_lux());
+    assert!(Lux(3775.0) > sensor.max_lux());
+    destroy(sensor);
+}
+
+#[test]
+fn max_lux_stays_within_device_range() {
+    let max = max_lux(IT::Ms25, Gain::OneEighth);
+    assert!(Lux(120793.0) < max);
+    assert!(Lux(120795.0) > max);
+    assert!(max_lux(IT::Ms50, Gain::OneEighth) < max);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn max_lux_is_not_compensated() {
+    let config = CFG_DEFAULT | (3 << 11);
+    let transactions = [I2cTrans::write(
+        DEV_ADDR,
+        vec![Reg::ALS_CONF, config as u8, (config >> 8) as u8],
+    )];
+    let mut sensor = new(&transactions);
+    sensor.set_gain(Gain::OneQuarter).await.unwrap();
+    assert_eq!(0.2304, sensor.resolution_lux_per_count());
+    assert_eq!(max_lux(IT::Ms100, Gain::OneQuarter), sensor.max_lux());
+    assert!((0.2304 * 65535.0 - sensor.max_lux().0).abs() < 0.01);
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn can_set_threshold_window() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WH], vec![0xFF, 0xFF]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 0xCD, 0xAB]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, 0x34, 0x12]),
+    ];
+    let mut sensor = new(&transactions);
+    sensor
+        .set_threshold_window(ThresholdWindow::between_raw(0x1234, 0xABCD))
+        .await
+        .unwrap();
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn can_set_threshold_window_lux() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WH], vec![0xFF, 0xFF]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 0xD0, 0x07]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, 0xE8, 0x03]),
+    ];
+    let mut sensor = new(&transactions);
+    sensor
+        .set_threshold_window(ThresholdWindow::between(Lux(57.61), Lux(115.21)))
+        .await
+        .unwrap();
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn rejects_inverted_threshold_window() {
+    let mut sensor = new(&[]);
+    let result = sensor
+        .set_threshold_window(ThresholdWindow::between(Lux(200.0), Lux(100.0)))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidInputData)));
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn restores_high_threshold_if_window_write_fails() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WH], vec![0x00, 0x10]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 0xCD, 0xAB]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, 0x34, 0x12]).with_error(ErrorKind::Other),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 0x00, 0x10]),
+    ];
+    let mut sensor = new(&transactions);
+    let result = sensor
+        .set_threshold_window(ThresholdWindow::between_raw(0x1234, 0xABCD))
+        .await;
+    assert!(matches!(result, Err(Error::I2C(ErrorKind::Other))));
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn writes_low_threshold_first_when_window_moves_down() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![0x00, 0x20]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, 0x34, 0x02]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 0xCD, 0x0B]),
+    ];
+    let mut sensor = new(&transactions);
+    sensor
+        .set_threshold_window(ThresholdWindow::between_raw(0x0234, 0x0BCD))
+        .await
+        .unwrap();
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn restores_low_threshold_if_window_write_fails() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![0x00, 0x20]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, 0x34, 0x02]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 0xCD, 0x0B]).with_error(ErrorKind::Other),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, 0x00, 0x20]),
+    ];
+    let mut sensor = new(&transactions);
+    let result = sensor
+        .set_threshold_window(ThresholdWindow::between_raw(0x0234, 0x0BCD))
+        .await;
+    assert!(matches!(result, Err(Error::I2C(ErrorKind::Other))));
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn lux_thresholds_are_updated_on_gain_change() {
+    let config = CFG_DEFAULT | (1 << 11);
+    let high = calculate_raw_threshold_value(IT::Ms100, Gain::Two, Lux(85.248));
+    let low = calculate_raw_threshold_value(IT::Ms100, Gain::Two, Lux(20.0));
+    let transactions = [
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 0xC8, 0x05]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, 0x5B, 0x01]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Reg::ALS_CONF, config as u8, (config >> 8) as u8],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, high as u8, (high >> 8) as u8]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, low as u8, (low >> 8) as u8]),
+    ];
+    let mut sensor = new(&transactions);
+    sensor.set_high_threshold_lux(Lux(85.248)).await.unwrap();
+    sensor.set_low_threshold_lux(Lux(20.0)).await.unwrap();
+    sensor.set_gain(Gain::Two).await.unwrap();
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn lux_thresholds_are_updated_on_it_change() {
+    let config = CFG_DEFAULT | (0b0001 << 6);
+    let high = calculate_raw_threshold_value(IT::Ms200, Gain::One, Lux(85.248));
+    let transactions = [
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 0xC8, 0x05]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Reg::ALS_CONF, config as u8, (config >> 8) as u8],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, high as u8, (high >> 8) as u8]),
+    ];
+    let mut sensor = new(&transactions);
+    sensor.set_high_threshold_lux(Lux(85.248)).await.unwrap();
+    sensor.set_integration_time(IT::Ms200).await.unwrap();
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn raw_thresholds_are_kept_on_gain_change() {
+    let config = CFG_DEFAULT | (1 << 11);
+    let transactions = [
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 0xC8, 0x05]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 0xCD, 0xAB]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Reg::ALS_CONF, config as u8, (config >> 8) as u8],
+        ),
+    ];
+    let mut sensor = new(&transactions);
+    sensor.set_high_threshold_lux(Lux(85.248)).await.unwrap();
+    sensor.set_high_threshold_raw(0xABCD).await.unwrap();
+    sensor.set_gain(Gain::Two).await.unwrap();
+    destroy(sensor);
+}
+
+#[cfg(not(feature = "async"))]
+fn int_low() -> Vec<PinTrans> {
+    vec![PinTrans::get(PinState::High), PinTrans::get(PinState::Low)]
+}
+
+#[cfg(feature = "async")]
+fn int_low() -> Vec<PinTrans> {
+    vec![PinTrans::wait_for_state(PinState::Low)]
+}
+
+/// Wait for a threshold event expecting the INT pin to be polled `polls`
+/// times while high.
+#[cfg(not(feature = "async"))]
+fn wait_for_threshold_event(
+    sensor: &mut Veml6030WithInterrupt<I2cMock, PinMock>,
+    polls: usize,
+) -> InterruptEvent {
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::delay_us(500); polls]);
+    let event = sensor.wait_for_threshold_event(&mut delay, 500).unwrap();
+    delay.done();
+    event
+}
+
+#[cfg(feature = "async")]
+async fn wait_for_threshold_event(
+    sensor: &mut Veml6030WithInterrupt<I2cMock, PinMock>,
+    _polls: usize,
+) -> InterruptEvent {
+    sensor.wait_for_threshold_event().await.unwrap()
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn can_wait_for_threshold_event() {
+    let transactions = [I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Reg::ALS_INT],
+        vec![0, (BF::INT_TH_HIGH >> 8) as u8],
+    )];
+    let mut sensor = Veml6030WithInterrupt::new(new(&transactions), PinMock::new(&int_low()));
+    let event = wait_for_threshold_event(&mut sensor, 1).await;
+    assert_eq!(InterruptEvent::TooHigh, event);
+    let (sensor, mut int) = sensor.destroy();
+    destroy(sensor);
+    int.done();
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn keeps_waiting_if_no_threshold_event_flagged() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_INT], vec![0, 0]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Reg::ALS_INT],
+            vec![0, (BF::INT_TH_LOW >> 8) as u8],
+        ),
+    ];
+    let pin_transactions = [int_low(), int_low()].concat();
+    let mut sensor =
+        Veml6030WithInterrupt::new(new(&transactions), PinMock::new(&pin_transactions));
+    let event = wait_for_threshold_event(&mut sensor, 2).await;
+    assert_eq!(InterruptEvent::TooLow, event);
+    let (sensor, mut int) = sensor.destroy();
+    destroy(sensor);
+    int.done();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn threshold_events_report_transitions() {
+    let high_flag = vec![0, (BF::INT_TH_HIGH >> 8) as u8];
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_INT], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_INT], high_flag.clone()),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0xE8, 0x03]),
+        // flags cleared by the previous read while still too high
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_INT], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0xE8, 0x03]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![100, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WH], vec![0xF4, 0x01]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_INT], high_flag),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_INT], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0x2C, 0x01]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![100, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WH], vec![0xF4, 0x01]),
+    ];
+    let delay = CheckedDelay::new(&[
+        DelayTrans::delay_ms(100),
+        DelayTrans::delay_ms(100),
+        DelayTrans::delay_ms(100),
+    ]);
+    let mut events = ThresholdEvents::new(new(&transactions), delay, 100);
+    let lux = convert_raw_als_to_lux(IT::Ms100, Gain::One, 1000);
+    assert_eq!(
+        ThresholdEvent::TooHigh(lux),
+        events.next_event().await.unwrap()
+    );
+    let lux = convert_raw_als_to_lux(IT::Ms100, Gain::One, 300);
+    assert_eq!(
+        ThresholdEvent::BackInRange(lux),
+        events.next_event().await.unwrap()
+    );
+    let (sensor, mut delay) = events.destroy();
+    destroy(sensor);
+    delay.done();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn threshold_events_compare_als_when_both_flags_are_set() {
+    let both_flags = ((BF::INT_TH_LOW | BF::INT_TH_HIGH) >> 8) as u8;
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_INT], vec![0, both_flags]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![50, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![100, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WH], vec![0xF4, 0x01]),
+    ];
+    let delay = CheckedDelay::new(&[]);
+    let mut events = ThresholdEvents::new(new(&transactions), delay, 100);
+    let lux = convert_raw_als_to_lux(IT::Ms100, Gain::One, 50);
+    assert_eq!(
+        ThresholdEvent::TooLow(lux),
+        events.next_event().await.unwrap()
+    );
+    let (sensor, mut delay) = events.destroy();
+    destroy(sensor);
+    delay.done();
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn threshold_tracking_recentres_window_on_event() {
+    let tracking = TrackingWindow::percent(10.0);
+    let window = |raw| {
+        tracking
+            .around(convert_raw_als_to_lux(IT::Ms100, Gain::One, raw))
+            .to_raw(IT::Ms100, Gain::One)
+    };
+    let (low1, high1) = window(1000);
+    let (low2, high2) = window(2000);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0xE8, 0x03]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WH], vec![0xFF, 0xFF]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, high1 as u8, (high1 >> 8) as u8]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, low1 as u8, (low1 >> 8) as u8]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_INT], vec![0, 0]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Reg::ALS_INT],
+            vec![0, (BF::INT_TH_HIGH >> 8) as u8],
+        ),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0xD0, 0x07]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Reg::ALS_WL],
+            vec![low1 as u8, (low1 >> 8) as u8],
+        ),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Reg::ALS_WH],
+            vec![high1 as u8, (high1 >> 8) as u8],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, high2 as u8, (high2 >> 8) as u8]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, low2 as u8, (low2 >> 8) as u8]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Reg::ALS_INT],
+            vec![0, (BF::INT_TH_HIGH >> 8) as u8],
+        ),
+    ];
+    let mut sensor = new(&transactions);
+    sensor.enable_threshold_tracking(tracking).await.unwrap();
+    sensor.read_interrupt_status().await.unwrap();
+    let status = sensor.read_interrupt_status().await.unwrap();
+    assert!(status.was_too_high);
+    sensor.disable_threshold_tracking();
+    sensor.read_interrupt_status().await.unwrap();
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn threshold_tracking_keeps_window_open_in_darkness() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WH], vec![0xFF, 0xFF]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 1, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, 0, 0]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Reg::ALS_INT],
+            vec![0, (BF::INT_TH_HIGH >> 8) as u8],
+        ),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![2, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WL], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_WH], vec![1, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, 3, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WL, 1, 0]),
+    ];
+    let mut sensor = new(&transactions);
+    let tracking = TrackingWindow::percent(10.0);
+    sensor.enable_threshold_tracking(tracking).await.unwrap();
+    let status = sensor.read_interrupt_status().await.unwrap();
+    assert!(status.was_too_high);
+    destroy(sensor);
+}
+
+get_test!(
+    int_event_too_low,
+    read_interrupt_event,
+    ALS_INT,
+    BF::INT_TH_LOW,
+    Some(InterruptEvent::TooLow)
+);
+
+get_test!(
+    int_event_both,
+    read_interrupt_event,
+    ALS_INT,
+    BF::INT_TH_LOW | BF::INT_TH_HIGH,
+    Some(InterruptEvent::Both)
+);
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn can_read_interrupt_status_with_als() {
+    let transactions = [
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Reg::ALS_INT],
+            vec![0, (BF::INT_TH_HIGH >> 8) as u8],
+        ),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0xCD, 0xAB]),
+    ];
+    let mut sensor = new(&transactions);
+    let result = sensor.read_interrupt_status_with_als().await.unwrap();
+    assert_eq!(
+        InterruptStatusWithAls {
+            status: InterruptStatus {
+                was_too_low: false,
+                was_too_high: true
+            },
+            raw_als: 0xABCD,
+            lux: convert_raw_als_to_lux(IT::Ms100, Gain::One, 0xABCD),
+        },
+        result
+    );
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn refresh_time_includes_psm_wait_time() {
+    let transactions = [
+        I2cTrans::write(DEV_ADDR, vec![Reg::PSM, (BF::PSM_EN | (1 << 1)) as u8, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::PSM, 0, 0]),
+    ];
+    let mut sensor = new(&transactions);
+    assert_eq!(100, sensor.refresh_time_ms());
+    sensor.enable_power_saving(PSM::Two).await.unwrap();
+    assert_eq!(1100, sensor.refresh_time_ms());
+    sensor.disable_power_saving().await.unwrap();
+    assert_eq!(100, sensor.refresh_time_ms());
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn can_set_persistence_duration() {
+    let config = CFG_DEFAULT | (2 << 4);
+    let transactions = [I2cTrans::write(
+        DEV_ADDR,
+        vec![Reg::ALS_CONF, config as u8, (config >> 8) as u8],
+    )];
+    let mut sensor = new(&transactions);
+    let result = sensor.set_persistence_duration(300).await.unwrap();
+    assert_eq!(FC::Four, result.fault_count);
+    assert_eq!(400, result.duration_ms);
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn set_persistence_duration_enables_power_saving() {
+    let config = CFG_DEFAULT | (2 << 4);
+    let transactions = [
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Reg::ALS_CONF, config as u8, (config >> 8) as u8],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Reg::PSM, BF::PSM_EN as u8, 0]),
+    ];
+    let mut sensor = new(&transactions);
+    let result = sensor.set_persistence_duration(1000).await.unwrap();
+    assert_eq!(
+        PersistenceConfig {
+            fault_count: FC::Four,
+            it: IT::Ms100,
+            psm: Some(PSM::One),
+            duration_ms: 1900,
+        },
+        result
+    );
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn rejects_unachievable_persistence_duration() {
+    let mut sensor = new(&[]);
+    let result = sensor.set_persistence_duration(40_000).await;
+    assert!(matches!(result, Err(Error::InvalidInputData)));
+    destroy(sensor);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn can_read_all() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0xE8, 0x03]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::WHITE], vec![0xD0, 0x07]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Reg::ALS_INT],
+            vec![0, (BF::INT_TH_LOW >> 8) as u8],
+        ),
+    ];
+    let mut sensor = new(&transactions);
+    let result = sensor.read_all().await.unwrap();
+    assert_eq!(
+        Measurement {
+            raw_als: 1000,
+            raw_white: 2000,
+            lux: convert_raw_als_to_lux(IT::Ms100, Gain::One, 1000),
+            interrupt_status: InterruptStatus {
+                was_too_low: true,
+                was_too_high: false
+            },
+        },
+        result
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn normalized_white_is_independent_of_configuration() {
+    let a = convert_raw_white_to_normalized(IT::Ms100, Gain::One, 3200);
+    let b = convert_raw_white_to_normalized(IT::Ms400, Gain::Two, 25600);
+    let c = convert_raw_white_to_normalized(IT::Ms25, Gain::OneEighth, 100);
+    assert!((a - 184.32).abs() < 0.01);
+    assert!((b - 184.32).abs() < 0.01);
+    assert!((c - 184.32).abs() < 0.01);
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn read_lux_hdr_uses_sensitive_setting() {
+    let transactions = [
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0x01, 0x08]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0x81, 0x08]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0xE8, 0x03]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0x81, 0x00]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0x01, 0x00]),
+    ];
+    let mut delay = CheckedDelay::new(&[DelayTrans::delay_ms(500), DelayTrans::delay_ms(500)]);
+    let mut sensor = new(&transactions);
+    let config = HdrConfig::default();
+    let measurement = sensor.read_lux_hdr(&config, &mut delay).await.unwrap();
+    assert_eq!(
+        HdrMeasurement {
+            lux: convert_raw_als_to_lux(IT::Ms400, Gain::Two, 1000),
+            raw_als: 1000,
+            sensitivity: config.sensitive,
+        },
+        measurement
+    );
+    assert_eq!(100, sensor.refresh_time_ms());
+    destroy(sensor);
+    delay.done();
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn read_lux_hdr_switches_when_saturated() {
+    let transactions = [
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0x01, 0x08]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0x81, 0x08]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0xFF, 0xFF]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0x81, 0x10]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0x01, 0x13]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0x10, 0x27]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0x01, 0x03]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0x01, 0x00]),
+    ];
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::delay_ms(500),
+        DelayTrans::delay_ms(425),
+        DelayTrans::delay_ms(125),
+    ]);
+    let mut sensor = new(&transactions);
+    let config = HdrConfig::default();
+    let measurement = sensor.read_lux_hdr(&config, &mut delay).await.unwrap();
+    assert_eq!(
+        HdrMeasurement {
+            lux: convert_raw_als_to_lux(IT::Ms25, Gain::OneEighth, 10000),
+            raw_als: 10000,
+            sensitivity: config.insensitive,
+        },
+        measurement
+    );
+    destroy(sensor);
+    delay.done();
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn read_lux_oversampled_waits_for_refresh() {
+    let transactions = [
+        I2cTrans::write(DEV_ADDR, vec![Reg::PSM, (BF::PSM_EN | (1 << 1)) as u8, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![100, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![200, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![150, 0]),
+    ];
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::delay_ms(1100),
+        DelayTrans::delay_ms(1100),
+        DelayTrans::delay_ms(1100),
+    ]);
+    let mut sensor = new(&transactions);
+    sensor.enable_power_saving(PSM::Two).await.unwrap();
+    let stats = sensor.read_lux_oversampled(3, &mut delay).await.unwrap();
+    let lux = |raw| convert_raw_als_to_lux(IT::Ms100, Gain::One, raw);
+    assert_eq!(lux(150).0, stats.mean.0);
+    assert_eq!(lux(100), stats.min);
+    assert_eq!(lux(200), stats.max);
+    assert!((stats.std_dev - (lux(200).0 - lux(100).0) / 6.0_f32.sqrt()).abs() < 1e-4);
+    assert_eq!(3, stats.samples);
+    destroy(sensor);
+    delay.done();
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn read_lux_oversampled_needs_samples() {
+    let mut delay = CheckedDelay::new(&[]);
+    let mut sensor = new(&[]);
+    match sensor.read_lux_oversampled(0, &mut delay).await {
+        Err(Error::InvalidInputData) => (),
+        _ => panic!("InvalidInputData not returned."),
+    }
+    destroy(sensor);
+    delay.done();
+}
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn filter_lux_readings() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![100, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![0xFF, 0xFF]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS], vec![110, 0]),
+    ];
+    let mut sensor = Veml6030WithFilter::new(new(&transactions), Median::<3>::new());
+    let lux = |raw| convert_raw_als_to_lux(IT::Ms100, Gain::One, raw);
+    assert_eq!(lux(100), sensor.read_lux().await.unwrap());
+    sensor.read_lux().await.unwrap();
+    assert_eq!(lux(110), sensor.read_lux().await.unwrap());
+    let (sensor, _) = sensor.destroy();
+    destroy(sensor);
+}
+
+macro_rules! health_test {
+    ($name:ident, $config:expr, $psm:expr, $expected:ident) => {
+        #[maybe_async_cfg::maybe(
+            sync(cfg(not(feature = "async"))),
+            async(feature = "async", keep_self)
+        )]
+        #[cfg_attr(feature = "async", tokio::test)]
+        #[cfg_attr(not(feature = "async"), test)]
+        async fn $name() {
+            let transactions = [
+                I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0, 0]),
+                I2cTrans::write_read(
+                    DEV_ADDR,
+                    vec![Reg::ALS_CONF],
+                    vec![$config as u8, ($config >> 8) as u8],
+                ),
+                I2cTrans::write_read(DEV_ADDR, vec![Reg::PSM], vec![$psm as u8, 0]),
+            ];
+            let mut sensor = new(&transactions);
+            sensor.enable().await.unwrap();
+            let health = sensor.check_health().await.unwrap();
+            assert_eq!(SensorHealth::$expected, health);
+            destroy(sensor);
+        }
+    };
+}
+
+health_test!(health_ok, 0, 0, Ok);
+health_test!(health_reset, CFG_DEFAULT, 0, Reset);
+health_test!(health_mismatch, BF::ALS_INT_EN, 0, Mismatch);
+health_test!(health_psm_mismatch, 0, BF::PSM_EN, Mismatch);
+
+#[maybe_async_cfg::maybe(sync(cfg(not(feature = "async"))), async(feature = "async", keep_self))]
+#[cfg_attr(feature = "async", tokio::test)]
+#[cfg_attr(not(feature = "async"), test)]
+async fn health_restores_configuration_after_reset() {
+    let psm = (BF::PSM_EN | (1 << 1)) as u8;
+    let lux = Lux(100.0);
+    let raw = calculate_raw_threshold_value(IT::Ms100, Gain::One, lux);
+    let transactions = [
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::PSM, psm, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, raw as u8, (raw >> 8) as u8]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_CONF], vec![CFG_DEFAULT as u8, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::PSM], vec![0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_WH, raw as u8, (raw >> 8) as u8]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::PSM, psm, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Reg::ALS_CONF, 0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::ALS_CONF], vec![0, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Reg::PSM], vec![psm, 0]),
+    ];
+    let mut sensor = new(&transactions);
+    sensor.enable().await.unwrap();
+    sensor.enable_power_saving(PSM::Two).await.unwrap();
+    sensor.set_high_threshold_lux(lux).await.unwrap();
+    let health = sensor.check_health_and_restore().await.unwrap();
+    assert_eq!(SensorHealth::Reset, health);
+    let health = sensor.check_health_and_restore().await.unwrap();
+    assert_eq!(SensorHealth::Ok, health);
+    destroy(sensor);
+}